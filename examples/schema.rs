@@ -1,6 +1,7 @@
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use cw_secret_network_address_alias::msg::{
-    HandleMsg, InitMsg, QueryAnswer, QueryMsg, SearchResponse,
+    BatchResolveResponse, BatchReverseLookupResponse, HandleMsg, InitMsg, QueryAnswer, QueryMsg,
+    ResolveAliasResponse, ReverseLookupResponse, SearchResponse,
 };
 use std::env::current_dir;
 use std::fs::create_dir_all;
@@ -11,9 +12,13 @@ fn main() {
     create_dir_all(&out_dir).unwrap();
     remove_schemas(&out_dir).unwrap();
 
+    export_schema(&schema_for!(BatchResolveResponse), &out_dir);
+    export_schema(&schema_for!(BatchReverseLookupResponse), &out_dir);
     export_schema(&schema_for!(HandleMsg), &out_dir);
     export_schema(&schema_for!(InitMsg), &out_dir);
     export_schema(&schema_for!(QueryAnswer), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(ResolveAliasResponse), &out_dir);
+    export_schema(&schema_for!(ReverseLookupResponse), &out_dir);
     export_schema(&schema_for!(SearchResponse), &out_dir);
 }
@@ -1,8 +1,9 @@
-use cosmwasm_std::{HumanAddr, StdError, StdResult};
+use crate::error::ContractError;
+use cosmwasm_std::{HumanAddr, StdResult};
 
 pub fn authorize(expected: HumanAddr, received: HumanAddr) -> StdResult<()> {
     if expected != received {
-        return Err(StdError::Unauthorized { backtrace: None });
+        return Err(ContractError::Unauthorized.into());
     }
 
     Ok(())
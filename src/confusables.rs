@@ -0,0 +1,73 @@
+// Characters that are commonly used to impersonate an alias, mapped to the latin
+// character they resemble. This is a subset of the Unicode confusables data (UTS #39)
+// covering digits and the cyrillic and greek letters that look like latin ones.
+const CONFUSABLES: &[(char, char)] = &[
+    ('0', 'o'),
+    ('1', 'l'),
+    ('|', 'l'),
+    ('ı', 'i'),
+    ('ɑ', 'a'),
+    ('ɡ', 'g'),
+    // Cyrillic
+    ('а', 'a'),
+    ('в', 'b'),
+    ('г', 'r'),
+    ('е', 'e'),
+    ('ё', 'e'),
+    ('һ', 'h'),
+    ('і', 'i'),
+    ('ї', 'i'),
+    ('ј', 'j'),
+    ('к', 'k'),
+    ('ӏ', 'l'),
+    ('м', 'm'),
+    ('н', 'h'),
+    ('о', 'o'),
+    ('п', 'n'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('т', 't'),
+    ('у', 'y'),
+    ('х', 'x'),
+    ('ѕ', 's'),
+    ('ԁ', 'd'),
+    ('ԛ', 'q'),
+    ('ԝ', 'w'),
+    ('ь', 'b'),
+    // Greek
+    ('α', 'a'),
+    ('β', 'b'),
+    ('γ', 'y'),
+    ('ε', 'e'),
+    ('η', 'n'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+    ('τ', 't'),
+    ('υ', 'u'),
+    ('χ', 'x'),
+    ('ω', 'w'),
+];
+
+// Letter sequences that render like a single letter
+const CONFUSABLE_SEQUENCES: &[(&str, &str)] = &[("rn", "m"), ("vv", "w")];
+
+// Two aliases with the same skeleton look alike
+pub fn skeleton(alias: &str) -> String {
+    let mut skeleton: String = alias
+        .chars()
+        .map(|c| {
+            CONFUSABLES
+                .iter()
+                .find(|(confusable, _)| *confusable == c)
+                .map_or(c, |(_, prototype)| *prototype)
+        })
+        .collect();
+    for (sequence, prototype) in CONFUSABLE_SEQUENCES {
+        skeleton = skeleton.replace(sequence, prototype);
+    }
+
+    skeleton
+}
@@ -1,60 +1,81 @@
 use crate::authorize::authorize;
+use crate::confusables::skeleton;
+use crate::error::ContractError;
 use crate::msg::ResponseStatus::Success;
 use crate::msg::{
-    AliasAttributes, HandleAnswer, HandleMsg, InitMsg, QueryAnswer, QueryMsg, ReceiveAnswer,
-    ReceiveMsg, SearchResponse,
+    AliasAttributes, AliasStatus, BatchResolveResponse, BatchReverseLookupResponse, HandleAnswer,
+    HandleMsg, InitMsg, QueryAnswer, QueryMsg, ReceiveAnswer, ReceiveMsg, ResolveAliasResponse,
+    ReverseLookupResponse, SearchResponse, TokenPrice,
 };
 use crate::state::{
-    AddressesAliasesReadonlyStorage, AddressesAliasesStorage, Alias, AliasesReadonlyStorage,
-    AliasesStorage, Config,
+    AddressAliases, AddressesAliasesReadonlyStorage, AddressesAliasesStorage, Alias,
+    AliasesListIdsStorage, AliasesListReadonlyStorage, AliasesListStorage, AliasesReadonlyStorage,
+    AliasesStorage, AliasesTrieNode, AliasesTrieReadonlyStorage, AliasesTrieStorage, Config,
+    LengthPrice, NativePayment, PaymentToken, ReservedAlias, ReservedAliasesListIdsStorage,
+    ReservedAliasesListReadonlyStorage, ReservedAliasesListStorage, ReservedAliasesReadonlyStorage,
+    ReservedAliasesStorage, SkeletonsReadonlyStorage, SkeletonsStorage, SubAliasesStorage,
 };
+use crate::validation::{validate_alias, validate_avatar_url};
 use cosmwasm_std::{
-    from_binary, to_binary, Api, Binary, Env, Extern, HandleResponse, HumanAddr, InitResponse,
-    Querier, QueryResult, StdError, StdResult, Storage, Uint128,
+    from_binary, to_binary, Api, BankMsg, Binary, Coin, CosmosMsg, Env, Extern, HandleResponse,
+    HumanAddr, InitResponse, Querier, QueryResult, StdError, StdResult, Storage, Uint128,
 };
 use secret_toolkit::snip20;
 use secret_toolkit::storage::{TypedStore, TypedStoreMut};
+use std::ops::Range;
+use unicode_normalization::UnicodeNormalization;
 
-pub const AMOUNT_FOR_TRANSACTION: u128 = 1_000_000;
+pub const ALIASES_LIST_NEXT_ID_KEY: &[u8] = b"aliases_list_next_id";
 pub const BLOCK_SIZE: usize = 1;
 pub const CONFIG_KEY: &[u8] = b"config";
+pub const RESERVED_ALIASES_LIST_NEXT_ID_KEY: &[u8] = b"reserved_aliases_list_next_id";
 
 pub fn init<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     msg: InitMsg,
 ) -> StdResult<InitResponse> {
+    check_max_batch_size(msg.max_batch_size)?;
     let mut config_store = TypedStoreMut::attach(&mut deps.storage);
     let config: Config = Config {
-        buttcoin: msg.buttcoin.clone(),
-        butt_lode: msg.butt_lode,
+        admin: env.message.sender.clone(),
+        native_payments: msg.native_payments,
+        payment_tokens: msg.payment_tokens,
+        pending_admin: None,
+        registration_period: msg.registration_period,
+        grace_period: msg.grace_period,
+        max_batch_size: msg.max_batch_size,
     };
     config_store.store(CONFIG_KEY, &config)?;
     for alias_attributes in msg.aliases {
+        // Imported aliases are not trimmed, stray spaces point at a broken import list
+        let alias_string: String = normalize_alias(&alias_attributes.alias);
+        validate_alias(&alias_string)?;
+        validate_avatar_url(alias_attributes.avatar_url.as_deref())?;
+        // The import list can't hold the same alias twice
+        if AliasesReadonlyStorage::from_storage(&deps.storage)
+            .get_alias(alias_string.as_bytes())?
+            .is_some()
+        {
+            return Err(ContractError::AliasTaken.into());
+        }
+        check_not_confusable(&deps.storage, &alias_string, None)?;
         let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
         let new_alias: Alias = Alias {
             avatar_url: alias_attributes.avatar_url,
+            expires_at: alias_attributes
+                .expires_at
+                .or_else(|| expires_at(&config, env.block.time)),
             human_address: alias_attributes.address.clone(),
+            parent: None,
         };
-        let alias_string_byte_slice: &[u8] = alias_attributes.alias.as_bytes();
-        alias_storage.set_alias(alias_string_byte_slice, new_alias);
-        // Check that the user doesn't already have an alias
-        let mut addresses_aliases_storage =
-            AddressesAliasesStorage::from_storage(&mut deps.storage);
-        addresses_aliases_storage.set_alias(
-            alias_attributes.address.0.as_bytes(),
-            &alias_attributes.alias,
-        )
+        alias_storage.set_alias(alias_string.as_bytes(), new_alias)?;
+        add_address_alias(&mut deps.storage, &alias_attributes.address, &alias_string)?;
+        index_alias(&mut deps.storage, &alias_string)?;
     }
 
     Ok(InitResponse {
-        messages: vec![snip20::register_receive_msg(
-            env.contract_code_hash.clone(),
-            None,
-            BLOCK_SIZE,
-            config.buttcoin.contract_hash,
-            config.buttcoin.address,
-        )?],
+        messages: register_receive_msgs(env.contract_code_hash, &config.payment_tokens)?,
         log: vec![],
     })
 }
@@ -65,10 +86,53 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
     msg: HandleMsg,
 ) -> StdResult<HandleResponse> {
     match msg {
+        HandleMsg::AcceptAdmin {} => try_accept_admin(deps, env),
+        HandleMsg::AddReservedAlias { alias } => try_add_reserved_alias(deps, env, alias),
+        HandleMsg::AssignReservedAlias { alias, address } => {
+            try_assign_reserved_alias(deps, env, alias, address)
+        }
+        HandleMsg::CancelAdminProposal {} => try_cancel_admin_proposal(deps, env),
+        HandleMsg::Create { alias, avatar_url } => {
+            try_create_with_native(deps, env, alias, avatar_url)
+        }
+        HandleMsg::CreateSubAlias {
+            alias,
+            address,
+            avatar_url,
+        } => try_create_sub_alias(deps, env, alias, address, avatar_url),
         HandleMsg::Destroy { alias } => try_destroy(deps, env, alias),
+        HandleMsg::ProposeAdmin { address } => try_propose_admin(deps, env, address),
+        HandleMsg::RemoveReservedAlias { alias } => try_remove_reserved_alias(deps, env, alias),
         HandleMsg::Receive {
             from, amount, msg, ..
         } => receive(deps, env, from, amount, msg),
+        HandleMsg::Renew { alias } => try_renew_with_native(deps, env, alias),
+        HandleMsg::RevokeSubAlias { alias } => try_revoke_sub_alias(deps, env, alias),
+        HandleMsg::SetPrimary { alias } => try_set_primary(deps, env, alias),
+        HandleMsg::Transfer { alias, recipient } => try_transfer(deps, env, alias, recipient),
+        HandleMsg::UpdateAlias { alias, avatar_url } => {
+            try_update_alias(deps, env, alias, avatar_url)
+        }
+        HandleMsg::UpdateSubAlias {
+            alias,
+            address,
+            avatar_url,
+        } => try_update_sub_alias(deps, env, alias, address, avatar_url),
+        HandleMsg::UpdateConfig {
+            payment_tokens,
+            native_payments,
+            registration_period,
+            grace_period,
+            max_batch_size,
+        } => try_update_config(
+            deps,
+            env,
+            payment_tokens,
+            native_payments,
+            registration_period,
+            grace_period,
+            max_batch_size,
+        ),
     }
 }
 
@@ -80,92 +144,269 @@ fn receive<S: Storage, A: Api, Q: Querier>(
     msg: Binary,
 ) -> StdResult<HandleResponse> {
     let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
-    // Ensure that the sent tokens are Buttcoins
-    authorize(config.buttcoin.address, env.message.sender)?;
-
-    // Ensure that amount sent in is 1 Buttcoin
-    if amount != Uint128(AMOUNT_FOR_TRANSACTION) {
-        return Err(StdError::generic_err(format!(
-            "Amount sent in: {}. Amount required {}.",
-            amount,
-            Uint128(AMOUNT_FOR_TRANSACTION)
-        )));
-    }
+    // Ensure that the sent tokens are an accepted payment token
+    let payment_token: PaymentToken = match config
+        .payment_tokens
+        .into_iter()
+        .find(|payment_token| payment_token.token.address == env.message.sender)
+    {
+        Some(payment_token) => payment_token,
+        None => return Err(ContractError::Unauthorized.into()),
+    };
 
     let msg: ReceiveMsg = from_binary(&msg)?;
     match msg {
-        ReceiveMsg::Create { alias, avatar_url } => try_create(deps, from, alias, avatar_url),
+        ReceiveMsg::Create { alias, avatar_url } => {
+            let payment_msgs = snip20_payment_msgs(&payment_token, &from, amount, &alias)?;
+            let mut response = try_create(deps, env, from, alias, avatar_url)?;
+            response.messages.extend(payment_msgs);
+            Ok(response)
+        }
+        ReceiveMsg::Rename {
+            from_alias,
+            to_alias,
+        } => {
+            let payment_msgs = snip20_payment_msgs(&payment_token, &from, amount, &to_alias)?;
+            let mut response = try_rename(deps, env, from, from_alias, to_alias)?;
+            response.messages.extend(payment_msgs);
+            Ok(response)
+        }
+        ReceiveMsg::Renew { alias } => {
+            let payment_msgs = snip20_payment_msgs(&payment_token, &from, amount, &alias)?;
+            let mut response = try_renew(deps, env, from, alias)?;
+            response.messages.extend(payment_msgs);
+            Ok(response)
+        }
+    }
+}
+
+fn try_accept_admin<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> StdResult<HandleResponse> {
+    let mut config_store = TypedStoreMut::attach(&mut deps.storage);
+    let mut config: Config = config_store.load(CONFIG_KEY)?;
+    if config.pending_admin.is_none() {
+        return Err(ContractError::NoAdminProposed.into());
+    }
+    let pending_admin: HumanAddr = config.pending_admin.unwrap();
+    authorize(pending_admin.clone(), env.message.sender)?;
+
+    config.admin = pending_admin;
+    config.pending_admin = None;
+    config_store.store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AcceptAdmin { status: Success })?),
+    })
+}
+
+fn try_add_reserved_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize(config.admin, env.message.sender)?;
+    let alias_string = format_alias(&alias_string);
+    validate_alias(&alias_string)?;
+    let mut reserved_aliases_storage = ReservedAliasesStorage::from_storage(&mut deps.storage);
+    if reserved_aliases_storage
+        .get_reserved_alias(alias_string.as_bytes())?
+        .is_some()
+    {
+        return Err(ContractError::AliasAlreadyReserved.into());
     }
+
+    reserved_aliases_storage.set_reserved_alias(
+        alias_string.as_bytes(),
+        &ReservedAlias {
+            alias: alias_string.clone(),
+            address: None,
+        },
+    )?;
+    let mut next_id_store = TypedStoreMut::attach(&mut deps.storage);
+    let id: u64 = next_id_store
+        .may_load(RESERVED_ALIASES_LIST_NEXT_ID_KEY)?
+        .unwrap_or_default();
+    next_id_store.store(RESERVED_ALIASES_LIST_NEXT_ID_KEY, &(id + 1))?;
+    ReservedAliasesListStorage::from_storage(&mut deps.storage)
+        .set_alias(&id.to_be_bytes(), &alias_string)?;
+    ReservedAliasesListIdsStorage::from_storage(&mut deps.storage)
+        .set_id(alias_string.as_bytes(), id)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AddReservedAlias {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_assign_reserved_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize(config.admin, env.message.sender)?;
+    let alias_string = format_alias(&alias_string);
+    let mut reserved_aliases_storage = ReservedAliasesStorage::from_storage(&mut deps.storage);
+    let mut reserved_alias: ReservedAlias = reserved_aliases_storage
+        .get_reserved_alias(alias_string.as_bytes())?
+        .ok_or(ContractError::ReservedAliasNotFound)?;
+
+    reserved_alias.address = Some(address);
+    reserved_aliases_storage.set_reserved_alias(alias_string.as_bytes(), &reserved_alias)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AssignReservedAlias {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_cancel_admin_proposal<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> StdResult<HandleResponse> {
+    let mut config_store = TypedStoreMut::attach(&mut deps.storage);
+    let mut config: Config = config_store.load(CONFIG_KEY)?;
+    authorize(config.admin.clone(), env.message.sender)?;
+
+    config.pending_admin = None;
+    config_store.store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::CancelAdminProposal {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_create_with_native<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias: String,
+    avatar_url: Option<String>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let payment_msgs = native_payment_msgs(&config, &env, &alias)?;
+    let mut response = try_create(deps, env.clone(), env.message.sender, alias, avatar_url)?;
+    response.messages.extend(payment_msgs);
+    response.data = Some(to_binary(&HandleAnswer::Create { status: Success })?);
+    Ok(response)
 }
 
 fn try_create<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
+    env: Env,
     from: HumanAddr,
     alias_string: String,
     avatar_url: Option<String>,
 ) -> StdResult<HandleResponse> {
-    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
-    let alias_string = alias_string.trim();
-    let alias_string_formatted = alias_string.to_lowercase().to_string();
+    let alias_string_formatted = format_alias(&alias_string);
     let alias_string_byte_slice: &[u8] = alias_string_formatted.as_bytes();
-    // Check alias size
-    if alias_string_byte_slice.len() > u8::MAX.into() {
-        return Err(StdError::generic_err("Alias is too long"));
-    }
-    // Check that Alias doesn't already exist
+    validate_alias(&alias_string_formatted)?;
+    validate_avatar_url(avatar_url.as_deref())?;
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    check_not_under_parent(&deps.storage, &alias_string_formatted)?;
+    check_not_reserved(&deps.storage, &alias_string_formatted, &from)?;
+    check_alias_available(
+        &mut deps.storage,
+        &config,
+        &alias_string_formatted,
+        env.block.time,
+    )?;
+    check_not_confusable(&deps.storage, &alias_string_formatted, None)?;
+
+    let new_alias = Alias {
+        avatar_url,
+        expires_at: expires_at(&config, env.block.time),
+        human_address: from.clone(),
+        parent: None,
+    };
     let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
-    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice);
-    if alias_object.is_none() {
-        let new_alias = Alias {
-            avatar_url: avatar_url,
-            human_address: from.clone(),
-        };
-        alias_storage.set_alias(alias_string_byte_slice, new_alias);
-        // Check that the user doesn't already have an alias
-        let mut addresses_aliases_storage =
-            AddressesAliasesStorage::from_storage(&mut deps.storage);
-        let alias_key: Option<Vec<u8>> = addresses_aliases_storage.get_alias(&from.to_string());
-        if alias_key.is_none() {
-            addresses_aliases_storage.set_alias(from.0.as_bytes(), &alias_string_formatted)
-        } else {
-            return Err(StdError::generic_err("Address already has an alias"));
-        }
-    } else {
-        return Err(StdError::generic_err("Alias has already been taken"));
-    }
+    alias_storage.set_alias(alias_string_byte_slice, new_alias)?;
+    add_address_alias(&mut deps.storage, &from, &alias_string_formatted)?;
+    index_alias(&mut deps.storage, &alias_string_formatted)?;
 
     Ok(HandleResponse {
-        messages: vec![snip20::transfer_msg(
-            config.butt_lode.address,
-            Uint128(AMOUNT_FOR_TRANSACTION),
-            None,
-            BLOCK_SIZE,
-            config.buttcoin.contract_hash,
-            config.buttcoin.address,
-        )?],
+        messages: vec![],
         log: vec![],
         data: Some(to_binary(&ReceiveAnswer::Create { status: Success })?),
     })
 }
 
+fn try_create_sub_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+    avatar_url: Option<String>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias_string = format_alias(&alias_string);
+    validate_alias(&alias_string)?;
+    validate_avatar_url(avatar_url.as_deref())?;
+    let parent: String = match parent_alias(&alias_string) {
+        Some(parent) => parent.to_string(),
+        None => return Err(ContractError::InvalidSubAlias.into()),
+    };
+    authorize_parent_owner(&deps.storage, &parent, env.message.sender)?;
+    check_not_reserved(&deps.storage, &alias_string, &address)?;
+    check_alias_available(&mut deps.storage, &config, &alias_string, env.block.time)?;
+    check_not_confusable(&deps.storage, &alias_string, None)?;
+
+    add_sub_alias(&mut deps.storage, &parent, &alias_string)?;
+    let new_alias = Alias {
+        avatar_url,
+        expires_at: None,
+        human_address: address.clone(),
+        parent: Some(parent),
+    };
+    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+    alias_storage.set_alias(alias_string.as_bytes(), new_alias)?;
+    add_address_alias(&mut deps.storage, &address, &alias_string)?;
+    index_alias(&mut deps.storage, &alias_string)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::CreateSubAlias {
+            status: Success,
+        })?),
+    })
+}
+
 fn try_destroy<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     alias_string: String,
 ) -> StdResult<HandleResponse> {
-    let alias_string = alias_string.trim().to_lowercase();
+    let alias_string = format_alias(&alias_string);
     let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
     let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
-    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice);
+    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice)?;
     if alias_object.is_none() {
-        return Err(StdError::not_found("Alias"));
+        return Err(ContractError::AliasNotFound.into());
     }
     let alias_object: Alias = alias_object.unwrap();
-    authorize(env.message.sender.clone(), alias_object.human_address)?;
+    authorize(
+        env.message.sender.clone(),
+        alias_object.human_address.clone(),
+    )?;
+    check_not_sub_alias(&alias_object)?;
 
-    alias_storage.remove_alias(alias_string_byte_slice);
-    let mut addresses_aliases_storage = AddressesAliasesStorage::from_storage(&mut deps.storage);
-    addresses_aliases_storage.remove_alias(env.message.sender.0.as_bytes());
+    remove_alias(&mut deps.storage, &alias_string, &env.message.sender)?;
 
     Ok(HandleResponse {
         messages: vec![],
@@ -174,396 +415,5052 @@ fn try_destroy<S: Storage, A: Api, Q: Querier>(
     })
 }
 
-pub fn query<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, msg: QueryMsg) -> QueryResult {
-    match msg {
-        QueryMsg::Config {} => query_config(deps),
-        QueryMsg::Search {
-            search_type,
-            mut search_value,
-        } => {
-            let alias_object: Option<Alias>;
-            let alias_attributes: AliasAttributes;
-            if search_type == "address" {
-                let addresses_aliases_storage =
-                    AddressesAliasesReadonlyStorage::from_storage(&deps.storage);
-                let alias_key = addresses_aliases_storage.get_alias(&search_value);
-                if alias_key.is_none() {
-                    return Err(StdError::not_found("Alias"));
-                }
-                search_value =
-                    String::from_utf8(alias_key.clone().unwrap()).expect("Found invalid UTF-8");
-            } else if search_type == "alias" {
-                search_value = search_value.trim().to_lowercase();
-            } else {
-                return Err(StdError::parse_err(
-                    "search_type",
-                    "must be address or alias.",
-                ));
-            }
+fn try_propose_admin<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let mut config_store = TypedStoreMut::attach(&mut deps.storage);
+    let mut config: Config = config_store.load(CONFIG_KEY)?;
+    authorize(config.admin.clone(), env.message.sender)?;
 
-            let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
-            alias_object = alias_storage.get_alias(search_value.as_bytes());
-            if alias_object.is_none() {
-                return Err(StdError::not_found("Alias"));
-            }
-            alias_attributes = AliasAttributes {
-                alias: search_value,
-                avatar_url: alias_object.clone().unwrap().avatar_url,
-                address: alias_object.unwrap().human_address,
-            };
+    config.pending_admin = Some(address);
+    config_store.store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ProposeAdmin { status: Success })?),
+    })
+}
+
+fn try_rename<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    from: HumanAddr,
+    from_alias: String,
+    to_alias: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let from_alias = format_alias(&from_alias);
+    let to_alias = format_alias(&to_alias);
+    validate_alias(&to_alias)?;
+    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+    let alias_object: Option<Alias> = alias_storage.get_alias(from_alias.as_bytes())?;
+    if alias_object.is_none() {
+        return Err(ContractError::AliasNotFound.into());
+    }
+    let alias_object: Alias = alias_object.unwrap();
+    authorize(from.clone(), alias_object.human_address.clone())?;
+    check_not_sub_alias(&alias_object)?;
+    check_not_under_parent(&deps.storage, &to_alias)?;
+    check_not_reserved(&deps.storage, &to_alias, &from)?;
+    check_alias_available(&mut deps.storage, &config, &to_alias, env.block.time)?;
+    check_not_confusable(&deps.storage, &to_alias, Some(&from_alias))?;
 
-            return Ok(to_binary(&SearchResponse {
-                r#type: "aliases".to_string(),
-                attributes: alias_attributes,
-            })?);
+    // Move the alias to its new key and swap it in the owner's aliases
+    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+    alias_storage.remove_alias(from_alias.as_bytes())?;
+    alias_storage.set_alias(to_alias.as_bytes(), alias_object)?;
+    reindex_alias(&mut deps.storage, &from_alias, &to_alias)?;
+    revoke_sub_aliases(&mut deps.storage, &from_alias)?;
+    let mut addresses_aliases_storage = AddressesAliasesStorage::from_storage(&mut deps.storage);
+    if let Some(mut address_aliases) = addresses_aliases_storage.get_aliases(&from.to_string())? {
+        for alias in address_aliases.aliases.iter_mut() {
+            if *alias == from_alias {
+                *alias = to_alias.clone();
+            }
         }
+        if address_aliases.primary == from_alias {
+            address_aliases.primary = to_alias;
+        }
+        addresses_aliases_storage.set_aliases(from.0.as_bytes(), &address_aliases)?;
     }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::Rename { status: Success })?),
+    })
 }
 
-fn query_config<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+fn try_remove_reserved_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
     let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize(config.admin, env.message.sender)?;
+    let alias_string = format_alias(&alias_string);
+    let mut reserved_aliases_storage = ReservedAliasesStorage::from_storage(&mut deps.storage);
+    if reserved_aliases_storage
+        .get_reserved_alias(alias_string.as_bytes())?
+        .is_none()
+    {
+        return Err(ContractError::ReservedAliasNotFound.into());
+    }
 
-    to_binary(&QueryAnswer::Config {
-        buttcoin: config.buttcoin,
-        butt_lode: config.butt_lode,
+    reserved_aliases_storage.remove_reserved_alias(alias_string.as_bytes())?;
+    let mut reserved_aliases_list_ids_storage =
+        ReservedAliasesListIdsStorage::from_storage(&mut deps.storage);
+    if let Some(id) = reserved_aliases_list_ids_storage.get_id(alias_string.as_bytes())? {
+        reserved_aliases_list_ids_storage.remove_id(alias_string.as_bytes())?;
+        ReservedAliasesListStorage::from_storage(&mut deps.storage)
+            .remove_alias(&id.to_be_bytes())?;
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RemoveReservedAlias {
+            status: Success,
+        })?),
     })
 }
 
-// === TESTS ===
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::state::SecretContract;
-    use cosmwasm_std::from_binary;
-    use cosmwasm_std::testing::*;
-    use cosmwasm_std::HumanAddr;
-    use std::any::Any;
+fn try_renew_with_native<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let payment_msgs = native_payment_msgs(&config, &env, &alias)?;
+    let mut response = try_renew(deps, env.clone(), env.message.sender, alias)?;
+    response.messages.extend(payment_msgs);
+    response.data = Some(to_binary(&HandleAnswer::Renew { status: Success })?);
+    Ok(response)
+}
 
-    fn extract_error_msg<T: Any>(error: StdResult<T>) -> String {
-        match error {
-            Ok(_response) => {
-                panic!("Unexpected query answer")
-            }
-            Err(err) => match err {
-                StdError::GenericErr { msg, .. } => msg,
-                StdError::NotFound { kind, .. } => format!("{} not found", kind),
-                StdError::Unauthorized { .. } => "Unauthorized".to_string(),
-                _ => panic!("Unexpected result from init"),
-            },
-        }
+fn try_renew<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    from: HumanAddr,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    if config.registration_period == 0 {
+        return Err(ContractError::AliasesDoNotExpire.into());
+    }
+    let alias_string = format_alias(&alias_string);
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice)?;
+    if alias_object.is_none() {
+        return Err(ContractError::AliasNotFound.into());
     }
+    let mut alias_object: Alias = alias_object.unwrap();
+    authorize(from, alias_object.human_address.clone())?;
 
-    //=== HELPER FUNCTIONS ===
-    fn init_helper() -> (
-        StdResult<InitResponse>,
-        Extern<MockStorage, MockApi, MockQuerier>,
-    ) {
-        let mut deps = mock_dependencies(20, &[]);
-        let env = mock_env(mock_user_address(), &[]);
+    // Extend from the current expiry, or from now if it has already lapsed
+    alias_object.expires_at = match alias_object.expires_at {
+        Some(expires_at) => Some(
+            expires_at
+                .max(env.block.time)
+                .saturating_add(config.registration_period),
+        ),
+        None => return Err(ContractError::AliasDoesNotExpire.into()),
+    };
+    alias_storage.set_alias(alias_string_byte_slice, alias_object)?;
 
-        let init_msg = InitMsg {
-            aliases: vec![AliasAttributes {
-                alias: "epstein didn't kill himself".to_string(),
-                address: HumanAddr::from("frump"),
-                avatar_url: None,
-            }],
-            buttcoin: mock_buttcoin(),
-            butt_lode: mock_butt_lode(),
-        };
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::Renew { status: Success })?),
+    })
+}
 
-        (init(&mut deps, env, init_msg), deps)
-    }
+fn try_revoke_sub_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = format_alias(&alias_string);
+    let alias_object: Alias = load_sub_alias(&deps.storage, &alias_string)?;
+    let parent: &str = alias_object.parent.as_ref().unwrap();
+    authorize_parent_owner(&deps.storage, parent, env.message.sender)?;
 
-    fn mock_buttcoin() -> SecretContract {
-        SecretContract {
-            address: HumanAddr("buttcoin-address".to_string()),
-            contract_hash: "buttcoin-contract-hash".to_string(),
-        }
-    }
+    remove_alias(
+        &mut deps.storage,
+        &alias_string,
+        &alias_object.human_address,
+    )?;
+    remove_sub_alias(&mut deps.storage, parent, &alias_string)?;
 
-    fn mock_butt_lode() -> SecretContract {
-        SecretContract {
-            address: HumanAddr("profit-sharing-contract-address".to_string()),
-            contract_hash: "profit-sharing-contract-hash".to_string(),
-        }
-    }
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RevokeSubAlias {
+            status: Success,
+        })?),
+    })
+}
 
-    fn mock_user_address() -> HumanAddr {
-        HumanAddr::from("some-geezer")
+fn try_set_primary<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = format_alias(&alias_string);
+    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string.as_bytes())?;
+    if alias_object.is_none() {
+        return Err(ContractError::AliasNotFound.into());
     }
+    authorize(
+        env.message.sender.clone(),
+        alias_object.unwrap().human_address,
+    )?;
 
-    // === TESTS ===
-    #[test]
-    fn test_try_destroy() {
-        let alias: &str = "nailbiter";
-
-        // Initialize
-        let (_init_result, mut deps) = init_helper();
+    let mut addresses_aliases_storage = AddressesAliasesStorage::from_storage(&mut deps.storage);
+    let mut address_aliases: AddressAliases = addresses_aliases_storage
+        .get_aliases(&env.message.sender.to_string())?
+        .ok_or(ContractError::AliasNotFound)?;
+    address_aliases.primary = alias_string;
+    addresses_aliases_storage.set_aliases(env.message.sender.0.as_bytes(), &address_aliases)?;
 
-        // when alias exists
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_string(),
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPrimary { status: Success })?),
+    })
+}
+
+fn try_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    recipient: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let alias_string = format_alias(&alias_string);
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice)?;
+    if alias_object.is_none() {
+        return Err(ContractError::AliasNotFound.into());
+    }
+    let mut alias_object: Alias = alias_object.unwrap();
+    authorize(
+        env.message.sender.clone(),
+        alias_object.human_address.clone(),
+    )?;
+    check_not_sub_alias(&alias_object)?;
+
+    alias_object.human_address = recipient.clone();
+    alias_storage.set_alias(alias_string_byte_slice, alias_object)?;
+    remove_address_alias(&mut deps.storage, &env.message.sender, &alias_string)?;
+    add_address_alias(&mut deps.storage, &recipient, &alias_string)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Transfer { status: Success })?),
+    })
+}
+
+fn try_update_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    avatar_url: Option<String>,
+) -> StdResult<HandleResponse> {
+    let alias_string = format_alias(&alias_string);
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice)?;
+    if alias_object.is_none() {
+        return Err(ContractError::AliasNotFound.into());
+    }
+    let mut alias_object: Alias = alias_object.unwrap();
+    authorize(env.message.sender, alias_object.human_address.clone())?;
+    check_not_sub_alias(&alias_object)?;
+    validate_avatar_url(avatar_url.as_deref())?;
+
+    alias_object.avatar_url = avatar_url;
+    alias_storage.set_alias(alias_string_byte_slice, alias_object)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::UpdateAlias { status: Success })?),
+    })
+}
+
+fn try_update_sub_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+    avatar_url: Option<String>,
+) -> StdResult<HandleResponse> {
+    let alias_string = format_alias(&alias_string);
+    let mut alias_object: Alias = load_sub_alias(&deps.storage, &alias_string)?;
+    authorize_parent_owner(
+        &deps.storage,
+        alias_object.parent.as_ref().unwrap(),
+        env.message.sender,
+    )?;
+    validate_avatar_url(avatar_url.as_deref())?;
+
+    // Point the sub-alias at its new address
+    if alias_object.human_address != address {
+        remove_address_alias(
+            &mut deps.storage,
+            &alias_object.human_address,
+            &alias_string,
+        )?;
+        add_address_alias(&mut deps.storage, &address, &alias_string)?;
+        alias_object.human_address = address;
+    }
+    alias_object.avatar_url = avatar_url;
+    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+    alias_storage.set_alias(alias_string.as_bytes(), alias_object)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::UpdateSubAlias {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_update_config<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    payment_tokens: Option<Vec<PaymentToken>>,
+    native_payments: Option<Vec<NativePayment>>,
+    registration_period: Option<u64>,
+    grace_period: Option<u64>,
+    max_batch_size: Option<u16>,
+) -> StdResult<HandleResponse> {
+    let mut config_store = TypedStoreMut::attach(&mut deps.storage);
+    let mut config: Config = config_store.load(CONFIG_KEY)?;
+    authorize(config.admin.clone(), env.message.sender)?;
+
+    let mut messages = vec![];
+    if let Some(payment_tokens) = payment_tokens {
+        // Register with every token so that they call receive on this contract
+        messages = register_receive_msgs(env.contract_code_hash, &payment_tokens)?;
+        config.payment_tokens = payment_tokens;
+    }
+    if let Some(native_payments) = native_payments {
+        config.native_payments = native_payments;
+    }
+    if let Some(registration_period) = registration_period {
+        config.registration_period = registration_period;
+    }
+    if let Some(grace_period) = grace_period {
+        config.grace_period = grace_period;
+    }
+    if let Some(max_batch_size) = max_batch_size {
+        check_max_batch_size(max_batch_size)?;
+        config.max_batch_size = max_batch_size;
+    }
+    config_store.store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::UpdateConfig { status: Success })?),
+    })
+}
+
+pub fn query<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, msg: QueryMsg) -> QueryResult {
+    match msg {
+        QueryMsg::Aliases { address } => query_aliases(deps, address),
+        QueryMsg::BatchResolve {
+            aliases,
+            current_time,
+        } => query_batch_resolve(deps, aliases, current_time),
+        QueryMsg::BatchReverseLookup {
+            addresses,
+            current_time,
+        } => query_batch_reverse_lookup(deps, addresses, current_time),
+        QueryMsg::Config {} => query_config(deps),
+        QueryMsg::ListAliases { start_after, limit } => {
+            query_list_aliases(deps, start_after, limit)
+        }
+        QueryMsg::Lookalike { alias } => query_lookalike(deps, alias),
+        QueryMsg::Price { alias } => query_price(deps, alias),
+        QueryMsg::ReservedAliases { start_after, limit } => {
+            query_reserved_aliases(deps, start_after, limit)
+        }
+        QueryMsg::ResolveAlias {
+            alias,
+            current_time,
+        } => query_resolve_alias(deps, alias, current_time),
+        QueryMsg::ReverseLookup {
+            address,
+            current_time,
+        } => query_reverse_lookup(deps, address, current_time),
+        QueryMsg::Search {
+            search_type,
+            search_value,
+            current_time,
+        } => query_search(deps, search_type, search_value, current_time),
+        QueryMsg::SearchPrefix { prefix, limit } => query_search_prefix(deps, prefix, limit),
+    }
+}
+
+fn query_aliases<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    address: HumanAddr,
+) -> QueryResult {
+    let addresses_aliases_storage = AddressesAliasesReadonlyStorage::from_storage(&deps.storage);
+    let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+    let mut primary: Option<String> = None;
+    let mut aliases: Vec<AliasAttributes> = vec![];
+    if let Some(address_aliases) = addresses_aliases_storage.get_aliases(&address.to_string())? {
+        for alias in address_aliases.aliases {
+            if let Some(alias_object) = alias_storage.get_alias(alias.as_bytes())? {
+                if alias == address_aliases.primary {
+                    primary = Some(alias.clone());
+                }
+                aliases.push(AliasAttributes {
+                    alias,
+                    avatar_url: alias_object.avatar_url,
+                    address: alias_object.human_address,
+                    expires_at: alias_object.expires_at,
+                });
+            }
+        }
+    }
+
+    to_binary(&QueryAnswer::Aliases { primary, aliases })
+}
+
+fn query_batch_resolve<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    aliases: Vec<String>,
+    current_time: Option<u64>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    check_batch_size(&config, aliases.len())?;
+    let mut results: Vec<Option<ResolveAliasResponse>> = vec![];
+    for alias in aliases {
+        results.push(resolve_alias(
+            &deps.storage,
+            &config,
+            format_alias(&alias),
+            current_time,
+        )?);
+    }
+
+    to_binary(&BatchResolveResponse { results })
+}
+
+fn query_batch_reverse_lookup<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    addresses: Vec<HumanAddr>,
+    current_time: Option<u64>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    check_batch_size(&config, addresses.len())?;
+    let mut results: Vec<Option<ReverseLookupResponse>> = vec![];
+    for address in addresses {
+        results.push(reverse_lookup(
+            &deps.storage,
+            &config,
+            address,
+            current_time,
+        )?);
+    }
+
+    to_binary(&BatchReverseLookupResponse { results })
+}
+
+fn query_config<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+
+    to_binary(&QueryAnswer::Config {
+        admin: config.admin,
+        pending_admin: config.pending_admin,
+        payment_tokens: config.payment_tokens,
+        native_payments: config.native_payments,
+        registration_period: config.registration_period,
+        grace_period: config.grace_period,
+        max_batch_size: config.max_batch_size,
+    })
+}
+
+fn query_list_aliases<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_after: Option<u64>,
+    limit: Option<u16>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let next_id: u64 = TypedStore::attach(&deps.storage)
+        .may_load(ALIASES_LIST_NEXT_ID_KEY)?
+        .unwrap_or_default();
+    let aliases_list_storage = AliasesListReadonlyStorage::from_storage(&deps.storage);
+    let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+    let (ids, next_start_after) = page_ids(start_after, page_limit(&config, limit), next_id);
+    let mut aliases: Vec<AliasAttributes> = vec![];
+    for id in ids {
+        let alias: String = match aliases_list_storage.get_alias(&id.to_be_bytes())? {
+            Some(alias) => alias,
+            None => continue,
+        };
+        if let Some(alias_object) = alias_storage.get_alias(alias.as_bytes())? {
+            aliases.push(AliasAttributes {
+                alias,
+                avatar_url: alias_object.avatar_url,
+                address: alias_object.human_address,
+                expires_at: alias_object.expires_at,
+            });
+        }
+    }
+
+    to_binary(&QueryAnswer::ListAliases {
+        aliases,
+        next_start_after,
+    })
+}
+
+fn query_lookalike<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias: String,
+) -> QueryResult {
+    let skeletons_storage = SkeletonsReadonlyStorage::from_storage(&deps.storage);
+    // The registered alias that the given alias would be mistaken for
+    let canonical: Option<String> =
+        skeletons_storage.get_alias(skeleton(&format_alias(&alias)).as_bytes())?;
+
+    to_binary(&QueryAnswer::Lookalike { alias: canonical })
+}
+
+fn query_resolve_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias: String,
+    current_time: Option<u64>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let resolve_alias_response: ResolveAliasResponse =
+        resolve_alias(&deps.storage, &config, format_alias(&alias), current_time)?
+            .ok_or(ContractError::AliasNotFound)?;
+
+    to_binary(&resolve_alias_response)
+}
+
+fn query_reverse_lookup<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    address: HumanAddr,
+    current_time: Option<u64>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let reverse_lookup_response: ReverseLookupResponse =
+        reverse_lookup(&deps.storage, &config, address, current_time)?
+            .ok_or(ContractError::AliasNotFound)?;
+
+    to_binary(&reverse_lookup_response)
+}
+
+// Kept for existing clients, resolve_alias and reverse_lookup replace it
+fn query_search<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    search_type: String,
+    search_value: String,
+    current_time: Option<u64>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let resolve_alias_response: Option<ResolveAliasResponse> = if search_type == "address" {
+        reverse_lookup(
+            &deps.storage,
+            &config,
+            HumanAddr::from(search_value),
+            current_time,
+        )?
+        .map(|reverse_lookup_response| ResolveAliasResponse {
+            alias: reverse_lookup_response.alias,
+            address: reverse_lookup_response.address,
+            avatar_url: reverse_lookup_response.avatar_url,
+            expires_at: reverse_lookup_response.expires_at,
+            grace_ends_at: reverse_lookup_response.grace_ends_at,
+            status: reverse_lookup_response.status,
+        })
+    } else if search_type == "alias" {
+        resolve_alias(
+            &deps.storage,
+            &config,
+            format_alias(&search_value),
+            current_time,
+        )?
+    } else {
+        return Err(ContractError::InvalidSearchType.into());
+    };
+    let resolve_alias_response: ResolveAliasResponse =
+        resolve_alias_response.ok_or(ContractError::AliasNotFound)?;
+
+    to_binary(&SearchResponse {
+        r#type: "aliases".to_string(),
+        attributes: AliasAttributes {
+            alias: resolve_alias_response.alias,
+            avatar_url: resolve_alias_response.avatar_url,
+            address: resolve_alias_response.address,
+            expires_at: resolve_alias_response.expires_at,
+        },
+        grace_ends_at: resolve_alias_response.grace_ends_at,
+        status: resolve_alias_response.status,
+    })
+}
+
+fn query_search_prefix<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    prefix: String,
+    limit: Option<u16>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let limit: usize = page_limit(&config, limit);
+    let aliases_trie_storage = AliasesTrieReadonlyStorage::from_storage(&deps.storage);
+    let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+    let mut aliases: Vec<AliasAttributes> = vec![];
+    // Depth first from the prefix's node, visiting children in order, gives lexical order
+    let mut stack: Vec<String> = vec![normalize_alias(&prefix)];
+    while let Some(key) = stack.pop() {
+        if aliases.len() >= limit {
+            break;
+        }
+        let node: AliasesTrieNode = match aliases_trie_storage.get_node(key.as_bytes())? {
+            Some(node) => node,
+            None => continue,
+        };
+        for c in node.children.iter().rev() {
+            stack.push(format!("{}{}", key, c));
+        }
+        if node.is_alias {
+            if let Some(alias_object) = alias_storage.get_alias(key.as_bytes())? {
+                aliases.push(AliasAttributes {
+                    alias: key,
+                    avatar_url: alias_object.avatar_url,
+                    address: alias_object.human_address,
+                    expires_at: alias_object.expires_at,
+                });
+            }
+        }
+    }
+
+    to_binary(&QueryAnswer::SearchPrefix { aliases })
+}
+
+fn query_price<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias: String,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias: String = format_alias(&alias);
+
+    to_binary(&QueryAnswer::Price {
+        prices: config
+            .payment_tokens
+            .iter()
+            .map(|payment_token| TokenPrice {
+                token: payment_token.token.clone(),
+                fee: fee_for_alias(payment_token.fee, &payment_token.length_prices, &alias),
+            })
+            .collect(),
+        native_prices: config
+            .native_payments
+            .iter()
+            .map(|native_payment| Coin {
+                denom: native_payment.denom.clone(),
+                amount: fee_for_alias(native_payment.fee, &native_payment.length_prices, &alias),
+            })
+            .collect(),
+        alias,
+    })
+}
+
+fn query_reserved_aliases<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_after: Option<u64>,
+    limit: Option<u16>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let next_id: u64 = TypedStore::attach(&deps.storage)
+        .may_load(RESERVED_ALIASES_LIST_NEXT_ID_KEY)?
+        .unwrap_or_default();
+    let reserved_aliases_list_storage =
+        ReservedAliasesListReadonlyStorage::from_storage(&deps.storage);
+    let reserved_aliases_storage = ReservedAliasesReadonlyStorage::from_storage(&deps.storage);
+    let (ids, next_start_after) = page_ids(start_after, page_limit(&config, limit), next_id);
+    let mut reserved_aliases: Vec<ReservedAlias> = vec![];
+    for id in ids {
+        let alias: String = match reserved_aliases_list_storage.get_alias(&id.to_be_bytes())? {
+            Some(alias) => alias,
+            None => continue,
+        };
+        if let Some(reserved_alias) =
+            reserved_aliases_storage.get_reserved_alias(alias.as_bytes())?
+        {
+            reserved_aliases.push(reserved_alias);
+        }
+    }
+
+    to_binary(&QueryAnswer::ReservedAliases {
+        reserved_aliases,
+        next_start_after,
+    })
+}
+
+// Queries can't see the block time, so callers pass in the current time. Aliases past their
+// grace period no longer resolve then, without it they resolve until they are claimed again.
+fn resolve_alias<S: Storage>(
+    storage: &S,
+    config: &Config,
+    alias: String,
+    current_time: Option<u64>,
+) -> StdResult<Option<ResolveAliasResponse>> {
+    let alias_storage = AliasesReadonlyStorage::from_storage(storage);
+    let alias_object: Alias = match alias_storage.get_alias(alias.as_bytes())? {
+        Some(alias_object) => alias_object,
+        None => return Ok(None),
+    };
+    let status: Option<AliasStatus> = match current_time {
+        Some(current_time) if is_released(&alias_object, current_time, config.grace_period) => {
+            return Ok(None)
+        }
+        Some(current_time) if is_expired(&alias_object, current_time) => {
+            Some(AliasStatus::ExpiredInGrace)
+        }
+        Some(_) => Some(AliasStatus::Active),
+        None => None,
+    };
+
+    Ok(Some(ResolveAliasResponse {
+        alias,
+        address: alias_object.human_address,
+        avatar_url: alias_object.avatar_url,
+        grace_ends_at: alias_object
+            .expires_at
+            .map(|expires_at| expires_at.saturating_add(config.grace_period)),
+        expires_at: alias_object.expires_at,
+        status,
+    }))
+}
+
+fn reverse_lookup<S: Storage>(
+    storage: &S,
+    config: &Config,
+    address: HumanAddr,
+    current_time: Option<u64>,
+) -> StdResult<Option<ReverseLookupResponse>> {
+    let addresses_aliases_storage = AddressesAliasesReadonlyStorage::from_storage(storage);
+    let primary: String = match addresses_aliases_storage.get_aliases(&address.to_string())? {
+        Some(address_aliases) => address_aliases.primary,
+        None => return Ok(None),
+    };
+
+    Ok(
+        resolve_alias(storage, config, primary, current_time)?.map(|resolve_alias_response| {
+            ReverseLookupResponse {
+                address,
+                alias: resolve_alias_response.alias,
+                avatar_url: resolve_alias_response.avatar_url,
+                expires_at: resolve_alias_response.expires_at,
+                grace_ends_at: resolve_alias_response.grace_ends_at,
+                status: resolve_alias_response.status,
+            }
+        }),
+    )
+}
+
+fn native_payment_msgs(config: &Config, env: &Env, alias: &str) -> StdResult<Vec<CosmosMsg>> {
+    // Nothing needs to be sent in when registration is free
+    if env.message.sent_funds.is_empty()
+        && config.native_payments.iter().any(|native_payment| {
+            fee_for_alias(
+                native_payment.fee,
+                &native_payment.length_prices,
+                &format_alias(alias),
+            )
+            .is_zero()
+        })
+    {
+        return Ok(vec![]);
+    }
+    // Ensure that a single accepted native coin was sent in
+    if env.message.sent_funds.len() != 1 {
+        return Err(ContractError::InvalidNativePayment.into());
+    }
+    let sent: &Coin = &env.message.sent_funds[0];
+    let native_payment: &NativePayment = match config
+        .native_payments
+        .iter()
+        .find(|native_payment| native_payment.denom == sent.denom)
+    {
+        Some(native_payment) => native_payment,
+        None => {
+            return Err(ContractError::DenomNotAccepted {
+                denom: sent.denom.clone(),
+            }
+            .into())
+        }
+    };
+
+    let (fee, excess) = split_payment(
+        native_payment.fee,
+        &native_payment.length_prices,
+        sent.amount,
+        alias,
+    )?;
+
+    let mut messages = vec![];
+    if !fee.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            from_address: env.contract.address.clone(),
+            to_address: native_payment.fee_recipient.clone(),
+            amount: vec![Coin {
+                denom: sent.denom.clone(),
+                amount: fee,
+            }],
+        }));
+    }
+    if !excess.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            from_address: env.contract.address.clone(),
+            to_address: env.message.sender.clone(),
+            amount: vec![Coin {
+                denom: sent.denom.clone(),
+                amount: excess,
+            }],
+        }));
+    }
+    Ok(messages)
+}
+
+fn snip20_payment_msgs(
+    payment_token: &PaymentToken,
+    from: &HumanAddr,
+    amount: Uint128,
+    alias: &str,
+) -> StdResult<Vec<CosmosMsg>> {
+    let (fee, excess) = split_payment(
+        payment_token.fee,
+        &payment_token.length_prices,
+        amount,
+        alias,
+    )?;
+
+    let mut messages = vec![];
+    if !fee.is_zero() {
+        messages.push(snip20::transfer_msg(
+            payment_token.fee_recipient.clone(),
+            fee,
+            None,
+            BLOCK_SIZE,
+            payment_token.token.contract_hash.clone(),
+            payment_token.token.address.clone(),
+        )?);
+    }
+    if !excess.is_zero() {
+        messages.push(snip20::transfer_msg(
+            from.clone(),
+            excess,
+            None,
+            BLOCK_SIZE,
+            payment_token.token.contract_hash.clone(),
+            payment_token.token.address.clone(),
+        )?);
+    }
+    Ok(messages)
+}
+
+fn authorize_parent_owner<S: Storage>(
+    storage: &S,
+    parent: &str,
+    sender: HumanAddr,
+) -> StdResult<()> {
+    let alias_storage = AliasesReadonlyStorage::from_storage(storage);
+    match alias_storage.get_alias(parent.as_bytes())? {
+        Some(parent_object) => authorize(sender, parent_object.human_address),
+        None => Err(ContractError::ParentAliasNotFound.into()),
+    }
+}
+
+// Aliases under a registered parent can only be created as sub-aliases by the parent's owner
+// Names under any registered ancestor belong to that ancestor's owner, even when the
+// levels in between are not registered
+fn check_not_under_parent<S: Storage>(storage: &S, alias: &str) -> StdResult<()> {
+    let alias_storage = AliasesReadonlyStorage::from_storage(storage);
+    let mut ancestor: Option<&str> = parent_alias(alias);
+    while let Some(parent) = ancestor {
+        if alias_storage.get_alias(parent.as_bytes())?.is_some() {
+            return Err(ContractError::NotParentOwner {
+                parent: parent.to_string(),
+            }
+            .into());
+        }
+        ancestor = parent_alias(parent);
+    }
+
+    Ok(())
+}
+
+fn check_not_reserved<S: Storage>(storage: &S, alias: &str, address: &HumanAddr) -> StdResult<()> {
+    let reserved_aliases_storage = ReservedAliasesReadonlyStorage::from_storage(storage);
+    if let Some(reserved_alias) = reserved_aliases_storage.get_reserved_alias(alias.as_bytes())? {
+        if reserved_alias.address.as_ref() != Some(address) {
+            return Err(ContractError::AliasReserved.into());
+        }
+    }
+
+    Ok(())
+}
+
+// Reject aliases that look like an existing alias, e.g. paypa1 when paypal exists
+// An alias being renamed may take on a lookalike of its old name
+fn check_not_confusable<S: Storage>(
+    storage: &S,
+    alias: &str,
+    renamed_from: Option<&str>,
+) -> StdResult<()> {
+    let skeletons_storage = SkeletonsReadonlyStorage::from_storage(storage);
+    if let Some(existing) = skeletons_storage.get_alias(skeleton(alias).as_bytes())? {
+        if existing != alias && Some(existing.as_str()) != renamed_from {
+            return Err(ContractError::AliasTooSimilar { existing }.into());
+        }
+    }
+
+    Ok(())
+}
+
+fn check_not_sub_alias(alias: &Alias) -> StdResult<()> {
+    if alias.parent.is_some() {
+        return Err(ContractError::ManagedByParent.into());
+    }
+
+    Ok(())
+}
+
+// Check that Alias doesn't already exist, unless it has expired and its grace period is over
+fn check_alias_available<S: Storage>(
+    storage: &mut S,
+    config: &Config,
+    alias: &str,
+    now: u64,
+) -> StdResult<()> {
+    let mut alias_storage = AliasesStorage::from_storage(storage);
+    let alias_object: Option<Alias> = alias_storage.get_alias(alias.as_bytes())?;
+    if let Some(alias_object) = alias_object {
+        if !is_expired(&alias_object, now) {
+            return Err(ContractError::AliasTaken.into());
+        }
+        if !is_released(&alias_object, now, config.grace_period) {
+            return Err(ContractError::AliasInGracePeriod.into());
+        }
+        remove_alias(storage, alias, &alias_object.human_address)?;
+    }
+
+    Ok(())
+}
+
+fn check_amount_sent(amount: Uint128, fee: Uint128) -> StdResult<()> {
+    if amount < fee {
+        return Err(ContractError::WrongAmount {
+            sent: amount,
+            required: fee,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+fn check_batch_size(config: &Config, batch_size: usize) -> StdResult<()> {
+    if batch_size > usize::from(config.max_batch_size) {
+        return Err(ContractError::BatchTooLarge {
+            max_batch_size: config.max_batch_size,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+fn check_max_batch_size(max_batch_size: u16) -> StdResult<()> {
+    if max_batch_size == 0 {
+        return Err(ContractError::InvalidMaxBatchSize.into());
+    }
+
+    Ok(())
+}
+
+// An empty page would hand back the cursor it was given, so a page holds at least one id
+fn page_limit(config: &Config, limit: Option<u16>) -> usize {
+    usize::from(
+        limit
+            .unwrap_or(config.max_batch_size)
+            .min(config.max_batch_size)
+            .max(1),
+    )
+}
+
+// The ids a page of a sequence id keyed list covers, some of which may belong to entries
+// that are gone, and the start_after of the next page unless it reached the end of the list
+fn page_ids(start_after: Option<u64>, limit: usize, next_id: u64) -> (Range<u64>, Option<u64>) {
+    let start: u64 = start_after.map_or(0, |start_after| start_after.saturating_add(1));
+    let end: u64 = start.saturating_add(limit as u64).min(next_id);
+    let next_start_after: Option<u64> = if end < next_id {
+        end.checked_sub(1)
+    } else {
+        None
+    };
+
+    (start..end, next_start_after)
+}
+
+fn expires_at(config: &Config, now: u64) -> Option<u64> {
+    if config.registration_period == 0 {
+        None
+    } else {
+        Some(now.saturating_add(config.registration_period))
+    }
+}
+
+fn is_expired(alias: &Alias, now: u64) -> bool {
+    match alias.expires_at {
+        Some(expires_at) => expires_at <= now,
+        None => false,
+    }
+}
+
+// Whether an expired alias is past its grace period and can be claimed by anyone
+fn is_released(alias: &Alias, now: u64, grace_period: u64) -> bool {
+    match alias.expires_at {
+        Some(expires_at) => expires_at.saturating_add(grace_period) <= now,
+        None => false,
+    }
+}
+
+fn fee_for_alias(fee: Uint128, length_prices: &[LengthPrice], alias: &str) -> Uint128 {
+    length_prices
+        .iter()
+        .find(|length_price| usize::from(length_price.length) == alias.len())
+        .map_or(fee, |length_price| length_price.fee)
+}
+
+// Splits an amount paid for an alias into the fee to forward and the excess to send back.
+// Either can be zero, registration is free when the fee is zero.
+fn split_payment(
+    fee: Uint128,
+    length_prices: &[LengthPrice],
+    amount: Uint128,
+    alias: &str,
+) -> StdResult<(Uint128, Uint128)> {
+    // Ensure that amount sent in covers the fee for this alias
+    let fee: Uint128 = fee_for_alias(fee, length_prices, &format_alias(alias));
+    check_amount_sent(amount, fee)?;
+    let excess: Uint128 = (amount - fee)?;
+
+    Ok((fee, excess))
+}
+
+// NFKC with lowercasing so that visually identical input maps to the same key
+fn format_alias(alias: &str) -> String {
+    normalize_alias(alias).trim().to_string()
+}
+
+fn normalize_alias(alias: &str) -> String {
+    let normalized: String = alias.nfkc().collect::<String>().to_lowercase();
+    normalized.nfkc().collect()
+}
+
+// Adds an alias to an address's aliases, making it the primary if it is the first
+fn add_address_alias<S: Storage>(
+    storage: &mut S,
+    address: &HumanAddr,
+    alias: &str,
+) -> StdResult<()> {
+    let mut addresses_aliases_storage = AddressesAliasesStorage::from_storage(storage);
+    let address_aliases = match addresses_aliases_storage.get_aliases(&address.to_string())? {
+        Some(mut address_aliases) => {
+            address_aliases.aliases.push(alias.to_string());
+            address_aliases
+        }
+        None => AddressAliases {
+            aliases: vec![alias.to_string()],
+            primary: alias.to_string(),
+        },
+    };
+    addresses_aliases_storage.set_aliases(address.0.as_bytes(), &address_aliases)
+}
+
+// Removes an alias and drops it from its owner's aliases, along with its sub-aliases
+fn remove_alias<S: Storage>(storage: &mut S, alias: &str, owner: &HumanAddr) -> StdResult<()> {
+    let mut alias_storage = AliasesStorage::from_storage(storage);
+    alias_storage.remove_alias(alias.as_bytes())?;
+    remove_address_alias(storage, owner, alias)?;
+    unindex_alias(storage, alias)?;
+    revoke_sub_aliases(storage, alias)
+}
+
+// Adds an alias to the lookalike index, the trie and the end of the list of aliases
+fn index_alias<S: Storage>(storage: &mut S, alias: &str) -> StdResult<()> {
+    add_skeleton(storage, alias)?;
+    add_trie_alias(storage, alias)?;
+    let mut next_id_store = TypedStoreMut::attach(storage);
+    let id: u64 = next_id_store
+        .may_load(ALIASES_LIST_NEXT_ID_KEY)?
+        .unwrap_or_default();
+    next_id_store.store(ALIASES_LIST_NEXT_ID_KEY, &(id + 1))?;
+    AliasesListStorage::from_storage(storage).set_alias(&id.to_be_bytes(), alias)?;
+    AliasesListIdsStorage::from_storage(storage).set_id(alias.as_bytes(), id)
+}
+
+// Removes an alias from the lookalike index, the trie and the list of aliases
+fn unindex_alias<S: Storage>(storage: &mut S, alias: &str) -> StdResult<()> {
+    remove_skeleton(storage, alias)?;
+    remove_trie_alias(storage, alias)?;
+    let mut aliases_list_ids_storage = AliasesListIdsStorage::from_storage(storage);
+    if let Some(id) = aliases_list_ids_storage.get_id(alias.as_bytes())? {
+        aliases_list_ids_storage.remove_id(alias.as_bytes())?;
+        AliasesListStorage::from_storage(storage).remove_alias(&id.to_be_bytes())?;
+    }
+
+    Ok(())
+}
+
+// Moves an alias to its new name in the lookalike index and the trie, keeping its place in
+// the list of aliases so that clients paging through it neither skip nor repeat it
+fn reindex_alias<S: Storage>(storage: &mut S, from_alias: &str, to_alias: &str) -> StdResult<()> {
+    remove_skeleton(storage, from_alias)?;
+    remove_trie_alias(storage, from_alias)?;
+    add_skeleton(storage, to_alias)?;
+    add_trie_alias(storage, to_alias)?;
+    let mut aliases_list_ids_storage = AliasesListIdsStorage::from_storage(storage);
+    if let Some(id) = aliases_list_ids_storage.get_id(from_alias.as_bytes())? {
+        aliases_list_ids_storage.remove_id(from_alias.as_bytes())?;
+        aliases_list_ids_storage.set_id(to_alias.as_bytes(), id)?;
+        AliasesListStorage::from_storage(storage).set_alias(&id.to_be_bytes(), to_alias)?;
+    }
+
+    Ok(())
+}
+
+fn add_skeleton<S: Storage>(storage: &mut S, alias: &str) -> StdResult<()> {
+    let mut skeletons_storage = SkeletonsStorage::from_storage(storage);
+    skeletons_storage.set_alias(skeleton(alias).as_bytes(), &alias.to_string())
+}
+
+fn add_trie_alias<S: Storage>(storage: &mut S, alias: &str) -> StdResult<()> {
+    let mut aliases_trie_storage = AliasesTrieStorage::from_storage(storage);
+    // Link every prefix of the alias to the character that follows it
+    for (index, c) in alias.char_indices() {
+        let prefix: &str = &alias[..index];
+        let mut node: AliasesTrieNode = aliases_trie_storage
+            .get_node(prefix.as_bytes())?
+            .unwrap_or_default();
+        if let Err(position) = node.children.binary_search(&c) {
+            node.children.insert(position, c);
+            aliases_trie_storage.set_node(prefix.as_bytes(), &node)?;
+        }
+    }
+    let mut node: AliasesTrieNode = aliases_trie_storage
+        .get_node(alias.as_bytes())?
+        .unwrap_or_default();
+    node.is_alias = true;
+    aliases_trie_storage.set_node(alias.as_bytes(), &node)
+}
+
+fn remove_trie_alias<S: Storage>(storage: &mut S, alias: &str) -> StdResult<()> {
+    let mut aliases_trie_storage = AliasesTrieStorage::from_storage(storage);
+    let mut node: AliasesTrieNode = match aliases_trie_storage.get_node(alias.as_bytes())? {
+        Some(node) => node,
+        None => return Ok(()),
+    };
+    node.is_alias = false;
+    // Prune the nodes that no longer lead to an alias
+    let mut prefix: &str = alias;
+    while node.children.is_empty() && !node.is_alias {
+        aliases_trie_storage.remove_node(prefix.as_bytes())?;
+        let c: char = match prefix.chars().last() {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+        prefix = &prefix[..prefix.len() - c.len_utf8()];
+        node = aliases_trie_storage
+            .get_node(prefix.as_bytes())?
+            .ok_or_else(|| StdError::not_found("Aliases trie node"))?;
+        node.children.retain(|child| *child != c);
+    }
+    aliases_trie_storage.set_node(prefix.as_bytes(), &node)
+}
+
+fn remove_skeleton<S: Storage>(storage: &mut S, alias: &str) -> StdResult<()> {
+    let mut skeletons_storage = SkeletonsStorage::from_storage(storage);
+    let skeleton: String = skeleton(alias);
+    // Only drop the entry if it belongs to this alias
+    if skeletons_storage.get_alias(skeleton.as_bytes())?.as_deref() == Some(alias) {
+        skeletons_storage.remove_alias(skeleton.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+// Drops an alias from an address's aliases, the oldest remaining alias becomes primary
+fn remove_address_alias<S: Storage>(
+    storage: &mut S,
+    address: &HumanAddr,
+    alias: &str,
+) -> StdResult<()> {
+    let mut addresses_aliases_storage = AddressesAliasesStorage::from_storage(storage);
+    if let Some(mut address_aliases) =
+        addresses_aliases_storage.get_aliases(&address.to_string())?
+    {
+        address_aliases
+            .aliases
+            .retain(|address_alias| address_alias != alias);
+        if address_aliases.aliases.is_empty() {
+            return addresses_aliases_storage.remove_aliases(address.0.as_bytes());
+        }
+        if address_aliases.primary == alias {
+            address_aliases.primary = address_aliases.aliases[0].clone();
+        }
+        addresses_aliases_storage.set_aliases(address.0.as_bytes(), &address_aliases)?;
+    }
+
+    Ok(())
+}
+
+fn add_sub_alias<S: Storage>(storage: &mut S, parent: &str, alias: &str) -> StdResult<()> {
+    let mut sub_aliases_storage = SubAliasesStorage::from_storage(storage);
+    let mut sub_aliases: Vec<String> = sub_aliases_storage
+        .get_sub_aliases(parent.as_bytes())?
+        .unwrap_or_default();
+    sub_aliases.push(alias.to_string());
+    sub_aliases_storage.set_sub_aliases(parent.as_bytes(), &sub_aliases)
+}
+
+fn remove_sub_alias<S: Storage>(storage: &mut S, parent: &str, alias: &str) -> StdResult<()> {
+    let mut sub_aliases_storage = SubAliasesStorage::from_storage(storage);
+    if let Some(mut sub_aliases) = sub_aliases_storage.get_sub_aliases(parent.as_bytes())? {
+        sub_aliases.retain(|sub_alias| sub_alias != alias);
+        if sub_aliases.is_empty() {
+            return sub_aliases_storage.remove_sub_aliases(parent.as_bytes());
+        }
+        sub_aliases_storage.set_sub_aliases(parent.as_bytes(), &sub_aliases)?;
+    }
+
+    Ok(())
+}
+
+// Sub-aliases only point at their parent by name, so they go when the parent is removed or
+// renamed. Otherwise whoever registers the parent next could take them over.
+fn revoke_sub_aliases<S: Storage>(storage: &mut S, parent: &str) -> StdResult<()> {
+    let mut sub_aliases_storage = SubAliasesStorage::from_storage(storage);
+    let sub_aliases: Vec<String> = sub_aliases_storage
+        .get_sub_aliases(parent.as_bytes())?
+        .unwrap_or_default();
+    sub_aliases_storage.remove_sub_aliases(parent.as_bytes())?;
+    for sub_alias in sub_aliases {
+        let sub_alias_object: Alias = load_sub_alias(storage, &sub_alias)?;
+        remove_alias(storage, &sub_alias, &sub_alias_object.human_address)?;
+    }
+
+    Ok(())
+}
+
+fn load_sub_alias<S: Storage>(storage: &S, alias: &str) -> StdResult<Alias> {
+    let alias_storage = AliasesReadonlyStorage::from_storage(storage);
+    let alias_object: Alias = alias_storage
+        .get_alias(alias.as_bytes())?
+        .ok_or(ContractError::AliasNotFound)?;
+    if alias_object.parent.is_none() {
+        return Err(ContractError::NotSubAlias.into());
+    }
+
+    Ok(alias_object)
+}
+
+// The alias after the first dot, e.g. btn.group for admin.btn.group
+fn parent_alias(alias: &str) -> Option<&str> {
+    match alias.find('.') {
+        Some(index) if index > 0 && index + 1 < alias.len() => Some(&alias[index + 1..]),
+        _ => None,
+    }
+}
+
+fn register_receive_msgs(
+    code_hash: String,
+    payment_tokens: &[PaymentToken],
+) -> StdResult<Vec<CosmosMsg>> {
+    payment_tokens
+        .iter()
+        .map(|payment_token| {
+            snip20::register_receive_msg(
+                code_hash.clone(),
+                None,
+                BLOCK_SIZE,
+                payment_token.token.contract_hash.clone(),
+                payment_token.token.address.clone(),
+            )
+        })
+        .collect()
+}
+
+// === TESTS ===
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{SecretContract, ADDRESSES_ALIASES_PREFIX, ALIASES_PREFIX};
+    use crate::validation::AVATAR_URL_MAX_LENGTH;
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::*;
+    use cosmwasm_std::{coins, HumanAddr};
+    use cosmwasm_storage::PrefixedStorage;
+    use std::any::Any;
+
+    fn extract_error_msg<T: Any>(error: StdResult<T>) -> String {
+        match error {
+            Ok(_response) => {
+                panic!("Unexpected query answer")
+            }
+            Err(err) => match err {
+                StdError::GenericErr { msg, .. } => msg,
+                StdError::NotFound { kind, .. } => format!("{} not found", kind),
+                StdError::Unauthorized { .. } => "Unauthorized".to_string(),
+                _ => panic!("Unexpected result from init"),
+            },
+        }
+    }
+
+    //=== HELPER FUNCTIONS ===
+    fn init_helper() -> (
+        StdResult<InitResponse>,
+        Extern<MockStorage, MockApi, MockQuerier>,
+    ) {
+        let mut deps = mock_dependencies(20, &[]);
+        let env = mock_env(mock_user_address(), &[]);
+
+        let init_msg = InitMsg {
+            aliases: vec![AliasAttributes {
+                alias: "epstein didn't kill himself".to_string(),
+                address: HumanAddr::from("frump"),
+                avatar_url: None,
+                expires_at: None,
+            }],
+            native_payments: vec![mock_native_payment()],
+            payment_tokens: vec![mock_payment_token()],
+            registration_period: 0,
+            grace_period: 0,
+            max_batch_size: 10,
+        };
+
+        (init(&mut deps, env, init_msg), deps)
+    }
+
+    fn create_alias_msg_helper(from: &str, alias: &str) -> HandleMsg {
+        HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        }
+    }
+
+    fn create_parent_alias_helper(deps: &mut Extern<MockStorage, MockApi, MockQuerier>) {
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "btn.group".to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        handle(deps, mock_env(mock_buttcoin().address, &[]), receive_msg).unwrap();
+    }
+
+    fn mock_buttcoin() -> SecretContract {
+        SecretContract {
+            address: HumanAddr("buttcoin-address".to_string()),
+            contract_hash: "buttcoin-contract-hash".to_string(),
+        }
+    }
+
+    fn mock_butt_lode() -> SecretContract {
+        SecretContract {
+            address: HumanAddr("profit-sharing-contract-address".to_string()),
+            contract_hash: "profit-sharing-contract-hash".to_string(),
+        }
+    }
+
+    fn mock_length_prices() -> Vec<LengthPrice> {
+        vec![
+            LengthPrice {
+                length: 1,
+                fee: Uint128(100_000_000),
+            },
+            LengthPrice {
+                length: 2,
+                fee: Uint128(50_000_000),
+            },
+        ]
+    }
+
+    fn mock_fee() -> Uint128 {
+        Uint128(1_000_000)
+    }
+
+    fn mock_native_payment() -> NativePayment {
+        NativePayment {
+            denom: "uscrt".to_string(),
+            fee_recipient: mock_butt_lode().address,
+            fee: Uint128(1_000_000),
+            length_prices: vec![],
+        }
+    }
+
+    fn mock_payment_token() -> PaymentToken {
+        PaymentToken {
+            token: mock_buttcoin(),
+            fee_recipient: mock_butt_lode().address,
+            fee: mock_fee(),
+            length_prices: vec![],
+        }
+    }
+
+    fn mock_user_address() -> HumanAddr {
+        HumanAddr::from("some-geezer")
+    }
+
+    // === TESTS ===
+    #[test]
+    fn test_try_add_reserved_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        let add_msg = HandleMsg::AddReservedAlias {
+            alias: " SecretNetwork ".to_string(),
+        };
+
+        // = when sent by someone other than the admin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            add_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when the alias is invalid
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::AddReservedAlias {
+                alias: "admin?".to_string(),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::AliasInvalidCharacter { character: '?' }.to_string()
+        );
+        // = * it is not listed
+        let query_result = query(
+            &deps,
+            QueryMsg::ReservedAliases {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::ReservedAliases {
+                reserved_aliases, ..
+            } => {
+                assert_eq!(reserved_aliases, vec![])
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when sent by the admin
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            add_msg.clone(),
+        );
+        // = * it returns an add reserved alias answer
+        let handle_answer: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_answer).unwrap(),
+            to_binary(&HandleAnswer::AddReservedAlias { status: Success }).unwrap()
+        );
+        // = * it lists the reserved alias
+        let query_result = query(
+            &deps,
+            QueryMsg::ReservedAliases {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::ReservedAliases {
+                reserved_aliases, ..
+            } => assert_eq!(
+                reserved_aliases,
+                vec![ReservedAlias {
+                    alias: "secretnetwork".to_string(),
+                    address: None
+                }]
+            ),
+            _ => panic!("Unexpected query answer"),
+        }
+        // = * nobody can create it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("frump", "secretnetwork"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasReserved.to_string());
+
+        // = when the alias is already reserved
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), add_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasAlreadyReserved.to_string());
+    }
+
+    #[test]
+    fn test_try_assign_reserved_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::AddReservedAlias {
+                alias: "secretnetwork".to_string(),
+            },
+        )
+        .unwrap();
+        let assign_msg = HandleMsg::AssignReservedAlias {
+            alias: "secretnetwork".to_string(),
+            address: HumanAddr::from("frump"),
+        };
+
+        // = when the alias is not reserved
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::AssignReservedAlias {
+                alias: "admin".to_string(),
+                address: HumanAddr::from("frump"),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::ReservedAliasNotFound.to_string());
+
+        // = when sent by someone other than the admin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            assign_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when sent by the admin
+        handle(&mut deps, mock_env(mock_user_address(), &[]), assign_msg).unwrap();
+        // = * other addresses cannot create it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("crump", "secretnetwork"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasReserved.to_string());
+        // = * the address it is reserved for can create it
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("frump", "secretnetwork"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_try_create_sub_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        create_parent_alias_helper(&mut deps);
+        let create_sub_alias_msg = HandleMsg::CreateSubAlias {
+            alias: "Admin.btn.group".to_string(),
+            address: HumanAddr::from("frump"),
+            avatar_url: None,
+        };
+
+        // = when the alias has no parent
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::CreateSubAlias {
+                alias: "admin".to_string(),
+                address: HumanAddr::from("frump"),
+                avatar_url: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::InvalidSubAlias.to_string());
+
+        // = when the parent alias does not exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::CreateSubAlias {
+                alias: "admin.btn".to_string(),
+                address: HumanAddr::from("frump"),
+                avatar_url: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::ParentAliasNotFound.to_string());
+
+        // = when sent by someone other than the parent's owner
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            create_sub_alias_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when someone tries to register it as a regular alias
+        // = * it raises an error
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "admin.btn.group".to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::NotParentOwner {
+                parent: "btn.group".to_string(),
+            }
+            .to_string()
+        );
+
+        // = when someone tries to take a name two levels under the parent
+        // = * it raises an error whether they register or rename into it
+        let receive_msgs = vec![
+            to_binary(&ReceiveMsg::Create {
+                alias: "x.y.btn.group".to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+            to_binary(&ReceiveMsg::Rename {
+                from_alias: "epstein didn't kill himself".to_string(),
+                to_alias: "x.y.btn.group".to_string(),
+            })
+            .unwrap(),
+        ];
+        for msg in receive_msgs {
+            let handle_result = handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                HandleMsg::Receive {
+                    sender: HumanAddr::from("frump"),
+                    from: HumanAddr::from("frump"),
+                    amount: mock_fee(),
+                    msg,
+                },
+            );
+            let error = extract_error_msg(handle_result);
+            assert_eq!(
+                error,
+                ContractError::NotParentOwner {
+                    parent: "btn.group".to_string(),
+                }
+                .to_string()
+            );
+        }
+
+        // = when sent by the parent's owner
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            create_sub_alias_msg.clone(),
+        );
+        // = * it returns a create sub alias answer
+        let handle_answer: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_answer).unwrap(),
+            to_binary(&HandleAnswer::CreateSubAlias { status: Success }).unwrap()
+        );
+        // = * it resolves to its own address
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "admin.btn.group".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("frump"));
+        // = * it is added to the address's aliases
+        let query_result = query(
+            &deps,
+            QueryMsg::Aliases {
+                address: HumanAddr::from("frump"),
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::Aliases { aliases, .. } => {
+                assert_eq!(aliases[1].alias, "admin.btn.group");
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+        // = * its holder cannot manage it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::Destroy {
+                alias: "admin.btn.group".to_string(),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::ManagedByParent.to_string());
+
+        // = when the sub-alias already exists
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            create_sub_alias_msg,
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasTaken.to_string());
+    }
+
+    #[test]
+    fn test_try_destroy() {
+        let alias: &str = "nailbiter";
+
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+
+        // when alias exists
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg.clone(),
+        );
+        handle_result.unwrap();
+
+        // = when user tries to delete an alias that does not exist
+        // = * it raises an error
+        let destroy_alias_message = HandleMsg::Destroy {
+            alias: "idonotexist".to_string(),
+        };
+        let res = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            destroy_alias_message,
+        );
+        let error = extract_error_msg(res);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when user tries to delete an alias that belongs to someone else
+        // = * it raises an error
+        let destroy_alias_message = HandleMsg::Destroy {
+            alias: alias.to_string(),
+        };
+        let res = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            destroy_alias_message,
+        );
+        let error = extract_error_msg(res);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when user destroy their own alias
+        // = * alias is removed
+        let destroy_alias_message = HandleMsg::Destroy {
+            alias: alias.to_string(),
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            destroy_alias_message,
+        )
+        .unwrap();
+        // Query destroyed alias via alias
+        let query_response = query(
+            &mut deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_response);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+        // Query destroyed alias via address
+        let query_response = query(
+            &mut deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: mock_user_address().to_string(),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_response);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+    }
+
+    #[test]
+    fn test_try_create() {
+        let alias = "   nail biter    ";
+        let avatar_url = "https://www.btn.group";
+
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+
+        // when creating an alias
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: Some(avatar_url.to_string()),
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+
+        // = when user sends in a token that is not buttcoin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            receive_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when user sends in buttcoin
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg.clone(),
+        );
+        let handle_result_unwrapped = handle_result.unwrap();
+
+        // = * it sends the BUTT to the BUTT lode
+        assert_eq!(
+            handle_result_unwrapped.messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                mock_fee(),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()],
+        );
+
+        // = * It creates alias without trailing and leading whitespaces
+        let search_response = query(
+            &mut deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nail biter".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(mock_user_address(), val.attributes.clone().address);
+        assert_eq!(
+            avatar_url,
+            val.attributes.clone().avatar_url.unwrap().to_string()
+        );
+        let search_response = query(
+            &mut deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: mock_user_address().to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!("nail biter", val.attributes.clone().alias.to_string());
+        assert_eq!(mock_user_address(), val.attributes.clone().address);
+        assert_eq!(
+            avatar_url,
+            val.attributes.clone().avatar_url.unwrap().to_string()
+        );
+
+        // = when alias already exists
+        let create_alias_message_two = ReceiveMsg::Create {
+            alias: "Epstein didn't kill himself".to_string(),
+            avatar_url: Some(avatar_url.to_string()),
+        };
+        let receive_msg_two = HandleMsg::Receive {
+            sender: HumanAddr::from("crump"),
+            from: HumanAddr::from("crump"),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message_two).unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg_two,
+        );
+
+        // = * it raises an error
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasTaken.to_string());
+
+        // = when alias exists but is a different case
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_uppercase().to_string(),
+            avatar_url: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+
+        // = * it raises an error
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, ContractError::AliasTaken.to_string());
+
+        // = when alias is too long
+        let alias = "Epstein didn't kill himself".repeat(20);
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_uppercase().to_string(),
+            avatar_url: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+
+        // = * it raises an error
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, ContractError::AliasTooLong.to_string());
+
+        // = when user already has an alias
+        let alias = "Epstein didn't kill himself".repeat(5);
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        // = * it keeps the first alias as primary
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "frump".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "epstein didn't kill himself");
+        // = * the new alias resolves to the user
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias,
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("frump"));
+    }
+
+    #[test]
+    fn test_try_accept_admin() {
+        let new_admin = HumanAddr::from("new-admin");
+
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+
+        // = when no admin has been proposed
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(new_admin.clone(), &[]),
+            HandleMsg::AcceptAdmin {},
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::NoAdminProposed.to_string());
+
+        // = when an admin has been proposed
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ProposeAdmin {
+                address: new_admin.clone(),
+            },
+        )
+        .unwrap();
+
+        // == when accepted by someone other than the proposed admin
+        // == * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::AcceptAdmin {},
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // == when accepted by the proposed admin
+        handle(
+            &mut deps,
+            mock_env(new_admin.clone(), &[]),
+            HandleMsg::AcceptAdmin {},
+        )
+        .unwrap();
+
+        // == * it sets the admin and clears the pending admin
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.admin, new_admin);
+        assert_eq!(config.pending_admin, None);
+
+        // == * the previous admin can no longer update the config
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ProposeAdmin {
+                address: mock_user_address(),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+    }
+
+    #[test]
+    fn test_try_create_with_invalid_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+
+        // = when the alias breaks the validation rules
+        // = * it raises a specific error
+        for (alias, expected_error) in &[
+            ("   ", ContractError::AliasTooShort),
+            (
+                "nail\nbiter",
+                ContractError::AliasInvalidCharacter { character: '\n' },
+            ),
+            (
+                "nail\u{200b}biter",
+                ContractError::AliasInvalidCharacter {
+                    character: '\u{200b}',
+                },
+            ),
+            (
+                "nail biter \u{1f485}",
+                ContractError::AliasInvalidCharacter {
+                    character: '\u{1f485}',
+                },
+            ),
+            (
+                "nail\tbiter",
+                ContractError::AliasInvalidCharacter { character: '\t' },
+            ),
+            ("nail  biter", ContractError::AliasRepeatedSpaces),
+        ] {
+            let handle_result = handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                create_alias_msg_helper("frump", alias),
+            );
+            let error = extract_error_msg(handle_result);
+            assert_eq!(error, expected_error.to_string());
+        }
+
+        // = when an alias imported on init breaks the validation rules
+        // = * it raises an error
+        let mut deps = mock_dependencies(20, &[]);
+        let init_msg = InitMsg {
+            aliases: vec![AliasAttributes {
+                alias: " nail biter".to_string(),
+                address: HumanAddr::from("frump"),
+                avatar_url: None,
+                expires_at: None,
+            }],
+            native_payments: vec![],
+            payment_tokens: vec![],
+            registration_period: 0,
+            grace_period: 0,
+            max_batch_size: 10,
+        };
+        let init_result = init(&mut deps, mock_env(mock_user_address(), &[]), init_msg);
+        let error = extract_error_msg(init_result);
+        assert_eq!(error, ContractError::AliasSurroundingSpace.to_string());
+
+        // = when the import list holds the same alias twice
+        // = * it raises an error
+        let mut deps = mock_dependencies(20, &[]);
+        let init_msg = InitMsg {
+            aliases: vec![
+                AliasAttributes {
+                    alias: "batman".to_string(),
+                    address: HumanAddr::from("frump"),
+                    avatar_url: None,
+                    expires_at: None,
+                },
+                AliasAttributes {
+                    alias: "Batman".to_string(),
+                    address: HumanAddr::from("crump"),
+                    avatar_url: None,
+                    expires_at: None,
+                },
+            ],
+            native_payments: vec![],
+            payment_tokens: vec![],
+            registration_period: 0,
+            grace_period: 0,
+            max_batch_size: 10,
+        };
+        let init_result = init(&mut deps, mock_env(mock_user_address(), &[]), init_msg);
+        let error = extract_error_msg(init_result);
+        assert_eq!(error, ContractError::AliasTaken.to_string());
+    }
+
+    #[test]
+    fn test_try_create_with_invalid_avatar_url() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        let create_msg_helper = |avatar_url: String| HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "nail biter".to_string(),
+                avatar_url: Some(avatar_url),
+            })
+            .unwrap(),
+        };
+
+        // = when avatar url is invalid
+        // = * it raises a specific error
+        let cases: Vec<(String, String)> = vec![
+            (
+                format!("https://{}", "a".repeat(AVATAR_URL_MAX_LENGTH)),
+                ContractError::AvatarUrlTooLong.to_string(),
+            ),
+            (
+                "https://btn.group/a vatar.png".to_string(),
+                ContractError::AvatarUrlInvalidCharacter.to_string(),
+            ),
+            (
+                "https://btn.group/\u{0}".to_string(),
+                ContractError::AvatarUrlInvalidCharacter.to_string(),
+            ),
+            (
+                "javascript:alert(1)".to_string(),
+                ContractError::AvatarUrlInvalidScheme.to_string(),
+            ),
+            (
+                "http://btn.group/avatar.png".to_string(),
+                ContractError::AvatarUrlInvalidScheme.to_string(),
+            ),
+            (
+                "ipfs://".to_string(),
+                ContractError::AvatarUrlMissingLocation.to_string(),
+            ),
+        ];
+        for (avatar_url, expected_error) in cases {
+            let handle_result = handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                create_msg_helper(avatar_url),
+            );
+            let error = extract_error_msg(handle_result);
+            assert_eq!(error, expected_error);
+        }
+
+        // = when avatar url is valid
+        // = * it creates the alias
+        for avatar_url in &[
+            "https://btn.group/avatar.png",
+            "ipfs://QmT5NvUtoM5nWFfrQdVrFtvGfKFmG7AHE8P34isapyhCxX",
+            "ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U",
+        ] {
+            handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                create_msg_helper(avatar_url.to_string()),
+            )
+            .unwrap();
+            handle(
+                &mut deps,
+                mock_env(HumanAddr::from("frump"), &[]),
+                HandleMsg::Destroy {
+                    alias: "nail biter".to_string(),
+                },
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_try_create_with_unicode_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("frump", "Batman"),
+        )
+        .unwrap();
+
+        // = when the same alias is typed in fullwidth characters
+        // = * it can not be registered again
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("crump", "\u{ff22}\u{ff21}\u{ff34}\u{ff2d}\u{ff21}\u{ff2e}"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasTaken.to_string());
+        // = * it resolves to what was registered
+        for search_value in &["\u{ff42}\u{ff41}\u{ff54}\u{ff4d}\u{ff41}\u{ff4e}", "BATMAN"] {
+            let search_response = query(
+                &deps,
+                QueryMsg::Search {
+                    search_type: "alias".to_string(),
+                    search_value: search_value.to_string(),
+                    current_time: None,
+                },
+            )
+            .unwrap();
+            let val: SearchResponse = from_binary(&search_response).unwrap();
+            assert_eq!(val.attributes.alias, "batman");
+            assert_eq!(val.attributes.address, HumanAddr::from("frump"));
+        }
+        // = * it can be destroyed
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::Destroy {
+                alias: "\u{ff22}\u{ff41}\u{ff54}\u{ff4d}\u{ff41}\u{ff4e}".to_string(),
+            },
+        )
+        .unwrap();
+
+        // = when the alias contains a letter outside a-z
+        // = * it raises the same error whether the accent is precomposed or combining
+        for alias in &["caf\u{e9}", "cafe\u{301}"] {
+            let handle_result = handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                create_alias_msg_helper("frump", alias),
+            );
+            let error = extract_error_msg(handle_result);
+            assert_eq!(
+                error,
+                ContractError::AliasInvalidCharacter {
+                    character: '\u{e9}'
+                }
+                .to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_create_with_confusable_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("frump", "paypal"),
+        )
+        .unwrap();
+
+        // = when alias looks like an existing alias
+        // = * it raises an error
+        for lookalike in &["paypa1", "PAYPA1", "payp\u{ff41}1"] {
+            let handle_result = handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                create_alias_msg_helper("crump", lookalike),
+            );
+            let error = extract_error_msg(handle_result);
+            assert_eq!(
+                error,
+                ContractError::AliasTooSimilar {
+                    existing: "paypal".to_string(),
+                }
+                .to_string()
+            );
+        }
+
+        // = when alias is renamed to a lookalike
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("crump", "modern"),
+        )
+        .unwrap();
+        let rename_msg_helper = |to_alias: &str| HandleMsg::Receive {
+            sender: HumanAddr::from("crump"),
+            from: HumanAddr::from("crump"),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Rename {
+                from_alias: "modern".to_string(),
+                to_alias: to_alias.to_string(),
+            })
+            .unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            rename_msg_helper("paypa1"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::AliasTooSimilar {
+                existing: "paypal".to_string(),
+            }
+            .to_string()
+        );
+
+        // = when alias is renamed
+        // = * the old alias no longer blocks its lookalikes
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            rename_msg_helper("batman"),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("crump", "modem"),
+        )
+        .unwrap();
+
+        // = when the existing alias is destroyed
+        // = * its lookalikes can be registered
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::Destroy {
+                alias: "paypal".to_string(),
+            },
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("crump", "paypa1"),
+        )
+        .unwrap();
+
+        // = when alias is renamed to a lookalike of itself
+        // = * it renames the alias
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            HandleMsg::Receive {
+                sender: HumanAddr::from("crump"),
+                from: HumanAddr::from("crump"),
+                amount: mock_fee(),
+                msg: to_binary(&ReceiveMsg::Rename {
+                    from_alias: "paypa1".to_string(),
+                    to_alias: "paypal".to_string(),
+                })
+                .unwrap(),
+            },
+        )
+        .unwrap();
+        // = * its lookalikes point at the new alias
+        let query_result = query(
+            &deps,
+            QueryMsg::Lookalike {
+                alias: "paypa1".to_string(),
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Lookalike { alias } => assert_eq!(alias, Some("paypal".to_string())),
+            _ => panic!("Unexpected query answer"),
+        }
+    }
+
+    #[test]
+    fn test_try_create_with_length_prices() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: Some(vec![PaymentToken {
+                    length_prices: mock_length_prices(),
+                    ..mock_payment_token()
+                }]),
+                native_payments: None,
+                registration_period: None,
+                grace_period: None,
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+        let create_alias_message = ReceiveMsg::Create {
+            alias: " S ".to_string(),
+            avatar_url: None,
+        };
+
+        // = when user sends in the fee for a short alias
+        // = * it raises an error
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::WrongAmount {
+                sent: Uint128(1000000),
+                required: Uint128(100000000),
+            }
+            .to_string()
+        );
+
+        // = when user sends in more than the price for the alias length
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(100_500_000),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+
+        // = * it sends the price to the BUTT lode and refunds the excess
+        assert_eq!(
+            handle_result.unwrap().messages,
+            vec![
+                snip20::transfer_msg(
+                    mock_butt_lode().address,
+                    Uint128(100_000_000),
+                    None,
+                    BLOCK_SIZE,
+                    mock_buttcoin().contract_hash,
+                    mock_buttcoin().address,
+                )
+                .unwrap(),
+                snip20::transfer_msg(
+                    mock_user_address(),
+                    Uint128(500_000),
+                    None,
+                    BLOCK_SIZE,
+                    mock_buttcoin().contract_hash,
+                    mock_buttcoin().address,
+                )
+                .unwrap()
+            ],
+        );
+    }
+
+    #[test]
+    fn test_try_create_with_native() {
+        let create_msg = HandleMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+        };
+
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+
+        // = when user sends in no funds
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            create_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::InvalidNativePayment.to_string());
+
+        // = when user sends in a denom that is not accepted
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &coins(1_000_000, "uatom")),
+            create_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::DenomNotAccepted {
+                denom: "uatom".to_string(),
+            }
+            .to_string()
+        );
+
+        // = when user sends in less than the fee
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &coins(999_999, "uscrt")),
+            create_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::WrongAmount {
+                sent: Uint128(999999),
+                required: Uint128(1000000),
+            }
+            .to_string()
+        );
+
+        // = when user sends in more than the fee
+        let env = mock_env(mock_user_address(), &coins(1_500_000, "uscrt"));
+        let handle_result = handle(&mut deps, env.clone(), create_msg);
+
+        // = * it sends the fee to the fee recipient and refunds the excess
+        assert_eq!(
+            handle_result.unwrap().messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: env.contract.address.clone(),
+                    to_address: mock_butt_lode().address,
+                    amount: coins(1_000_000, "uscrt"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: env.contract.address,
+                    to_address: mock_user_address(),
+                    amount: coins(500_000, "uscrt"),
+                })
+            ],
+        );
+
+        // = * it creates the alias for the sender
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nailbiter".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(mock_user_address(), val.attributes.address);
+
+        // = when the alias has already been taken
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("crump"), &coins(1_000_000, "uscrt")),
+            HandleMsg::Create {
+                alias: "NailBiter".to_string(),
+                avatar_url: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasTaken.to_string());
+    }
+
+    #[test]
+    fn test_try_create_with_zero_fee() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: Some(vec![PaymentToken {
+                    fee: Uint128(0),
+                    ..mock_payment_token()
+                }]),
+                native_payments: None,
+                registration_period: None,
+                grace_period: None,
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+
+        // = when user sends in the old fee
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "freebie".to_string(),
+            avatar_url: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+
+        // = * it creates the alias and refunds everything sent in
+        assert_eq!(
+            handle_result.unwrap().messages,
+            vec![snip20::transfer_msg(
+                mock_user_address(),
+                mock_fee(),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()],
+        );
+
+        // = when user sends in nothing
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "freebie two".to_string(),
+            avatar_url: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("crump"),
+            from: HumanAddr::from("crump"),
+            amount: Uint128(0),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+
+        // = * it creates the alias without sending anything
+        assert_eq!(handle_result.unwrap().messages, vec![]);
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "freebie".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(mock_user_address(), val.attributes.address);
+
+        // = when the native fee is zero and user sends in no funds
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: None,
+                native_payments: Some(vec![NativePayment {
+                    fee: Uint128(0),
+                    ..mock_native_payment()
+                }]),
+                registration_period: None,
+                grace_period: None,
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("trump"), &[]),
+            HandleMsg::Create {
+                alias: "freebie three".to_string(),
+                avatar_url: None,
+            },
+        );
+
+        // = * it creates the alias without sending anything
+        assert_eq!(handle_result.unwrap().messages, vec![]);
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "freebie three".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(HumanAddr::from("trump"), val.attributes.address);
+    }
+
+    #[test]
+    fn test_try_propose_admin() {
+        let mistyped_admin = HumanAddr::from("new-admni");
+        let new_admin = HumanAddr::from("new-admin");
+
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+
+        // = when called by someone other than the admin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(new_admin.clone(), &[]),
+            HandleMsg::ProposeAdmin {
+                address: new_admin.clone(),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when called by the admin
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ProposeAdmin {
+                address: mistyped_admin.clone(),
+            },
+        )
+        .unwrap();
+
+        // = * it sets the pending admin without changing the admin
+        let query_result = query(&deps, QueryMsg::Config {}).unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Config {
+                admin,
+                pending_admin,
+                ..
+            } => {
+                assert_eq!(admin, mock_user_address());
+                assert_eq!(pending_admin, Some(mistyped_admin.clone()));
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when the proposal is cancelled by someone other than the admin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mistyped_admin.clone(), &[]),
+            HandleMsg::CancelAdminProposal {},
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when the proposal is cancelled by the admin
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::CancelAdminProposal {},
+        )
+        .unwrap();
+
+        // = * it clears the pending admin
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.admin, mock_user_address());
+        assert_eq!(config.pending_admin, None);
+
+        // = * the cancelled address can not accept
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mistyped_admin.clone(), &[]),
+            HandleMsg::AcceptAdmin {},
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::NoAdminProposed.to_string());
+
+        // = when the admin proposes again
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ProposeAdmin {
+                address: mistyped_admin.clone(),
+            },
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ProposeAdmin {
+                address: new_admin.clone(),
+            },
+        )
+        .unwrap();
+
+        // = * it replaces the previous proposal
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.pending_admin, Some(new_admin.clone()));
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mistyped_admin, &[]),
+            HandleMsg::AcceptAdmin {},
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+        handle(
+            &mut deps,
+            mock_env(new_admin.clone(), &[]),
+            HandleMsg::AcceptAdmin {},
+        )
+        .unwrap();
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.admin, new_admin);
+    }
+
+    #[test]
+    fn test_try_remove_reserved_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        for alias in &["secretnetwork", "admin"] {
+            handle(
+                &mut deps,
+                mock_env(mock_user_address(), &[]),
+                HandleMsg::AddReservedAlias {
+                    alias: alias.to_string(),
+                },
+            )
+            .unwrap();
+        }
+        let remove_msg = HandleMsg::RemoveReservedAlias {
+            alias: "secretnetwork".to_string(),
+        };
+
+        // = when sent by someone other than the admin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            remove_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when sent by the admin
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            remove_msg.clone(),
+        )
+        .unwrap();
+        // = * it is no longer listed
+        let query_result = query(
+            &deps,
+            QueryMsg::ReservedAliases {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::ReservedAliases {
+                reserved_aliases, ..
+            } => assert_eq!(
+                reserved_aliases,
+                vec![ReservedAlias {
+                    alias: "admin".to_string(),
+                    address: None
+                }]
+            ),
+            _ => panic!("Unexpected query answer"),
+        }
+        // = * pages carry on past it
+        let reserved_aliases_page = |start_after: Option<u64>| {
+            let query_result = query(
+                &deps,
+                QueryMsg::ReservedAliases {
+                    start_after,
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::ReservedAliases {
+                    reserved_aliases,
+                    next_start_after,
+                } => (reserved_aliases, next_start_after),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+        assert_eq!(reserved_aliases_page(None), (vec![], Some(0)));
+        assert_eq!(
+            reserved_aliases_page(Some(0)),
+            (
+                vec![ReservedAlias {
+                    alias: "admin".to_string(),
+                    address: None
+                }],
+                None
+            )
+        );
+        // = * anyone can create it
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("crump", "secretnetwork"),
+        )
+        .unwrap();
+
+        // = when the alias is not reserved
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), remove_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::ReservedAliasNotFound.to_string());
+    }
+
+    #[test]
+    fn test_try_rename() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        let rename_msg =
+            |from: &str, from_alias: &str, to_alias: &str, amount: Uint128| HandleMsg::Receive {
+                sender: HumanAddr::from(from),
+                from: HumanAddr::from(from),
+                amount,
+                msg: to_binary(&ReceiveMsg::Rename {
+                    from_alias: from_alias.to_string(),
+                    to_alias: to_alias.to_string(),
+                })
+                .unwrap(),
+            };
+        let create_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "nail bitter".to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_msg,
+        )
+        .unwrap();
+
+        // = when the alias does not exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            rename_msg("some-geezer", "toe biter", "nail biter", mock_fee()),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when sent by someone other than the owner
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            rename_msg("frump", "nail bitter", "nail biter", mock_fee()),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when the new alias has already been taken
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            rename_msg(
+                "some-geezer",
+                "nail bitter",
+                "Epstein didn't kill himself",
+                mock_fee(),
+            ),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasTaken.to_string());
+
+        // = when the fee is not paid
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            rename_msg("some-geezer", "nail bitter", "nail biter", Uint128(1)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::WrongAmount {
+                sent: Uint128(1),
+                required: Uint128(1000000),
+            }
+            .to_string()
+        );
+
+        // = when the owner renames to a free alias
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            rename_msg("some-geezer", "Nail Bitter", " nail biter ", mock_fee()),
+        );
+        // = * it sends the fee to the fee recipient
+        assert_eq!(
+            handle_result.unwrap().messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                mock_fee(),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()]
+        );
+        // = * the old alias no longer resolves
+        let query_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nail bitter".to_string(),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_response);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+        // = * the new alias resolves to the owner
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nail biter".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, mock_user_address());
+        // = * the owner resolves to the new alias
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: mock_user_address().to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "nail biter");
+    }
+
+    #[test]
+    fn test_try_renew() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "nail biter";
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        let created_at: u64 = env.block.time;
+        let renew_alias_message = ReceiveMsg::Renew {
+            alias: alias.to_string(),
+        };
+
+        // = when aliases do not expire
+        // = * it raises an error
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: HumanAddr::from("frump"),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Renew {
+                alias: "epstein didn't kill himself".to_string(),
+            })
+            .unwrap(),
+        };
+        let handle_result = handle(&mut deps, env.clone(), receive_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasesDoNotExpire.to_string());
+
+        // = when aliases expire
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: None,
+                native_payments: None,
+                registration_period: Some(100),
+                grace_period: None,
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+        // == when the alias does not exist
+        // == * it raises an error
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&renew_alias_message).unwrap(),
+        };
+        let handle_result = handle(&mut deps, env.clone(), receive_msg.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // == when the alias was registered before expiry was turned on
+        // == * it raises an error
+        let receive_msg_for_frump = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: HumanAddr::from("frump"),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Renew {
+                alias: "epstein didn't kill himself".to_string(),
+            })
+            .unwrap(),
+        };
+        let handle_result = handle(&mut deps, env.clone(), receive_msg_for_frump);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasDoesNotExpire.to_string());
+
+        // == when the alias exists
+        let create_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        handle(&mut deps, env.clone(), create_msg).unwrap();
+        let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+        let alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        // == * it sets the expiry from the registration period
+        assert_eq!(alias_object.expires_at, Some(created_at + 100));
+
+        // === when renewed by someone else
+        // === * it raises an error
+        let receive_msg_for_frump = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: HumanAddr::from("frump"),
+            amount: mock_fee(),
+            msg: to_binary(&renew_alias_message).unwrap(),
+        };
+        let handle_result = handle(&mut deps, env.clone(), receive_msg_for_frump);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // === when the fee is not paid
+        // === * it raises an error
+        let short_receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(1),
+            msg: to_binary(&renew_alias_message).unwrap(),
+        };
+        let handle_result = handle(&mut deps, env.clone(), short_receive_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::WrongAmount {
+                sent: Uint128(1),
+                required: Uint128(1000000),
+            }
+            .to_string()
+        );
+
+        // === when renewed by the owner before it expires
+        let handle_result = handle(&mut deps, env.clone(), receive_msg.clone());
+        // === * it extends the expiry by the registration period
+        let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+        let alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        assert_eq!(alias_object.expires_at, Some(created_at + 200));
+        // === * it sends the fee to the fee recipient
+        assert_eq!(
+            handle_result.unwrap().messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                mock_fee(),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()]
+        );
+
+        // === when the alias has expired
+        env.block.time = created_at + 500;
+        handle(&mut deps, env.clone(), receive_msg.clone()).unwrap();
+        // === * it extends the expiry from now
+        let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+        let alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        assert_eq!(alias_object.expires_at, Some(created_at + 600));
+
+        // = when renewed with native SCRT
+        let mut native_env = mock_env(mock_user_address(), &coins(1_000_000, "uscrt"));
+        native_env.block.time = created_at + 500;
+        let handle_result = handle(
+            &mut deps,
+            native_env,
+            HandleMsg::Renew {
+                alias: alias.to_string(),
+            },
+        );
+        // = * it extends the expiry
+        let handle_result_unwrapped = handle_result.unwrap();
+        let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+        let alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        assert_eq!(alias_object.expires_at, Some(created_at + 700));
+        // = * it returns a renew answer
+        let handle_answer: HandleAnswer =
+            from_binary(&handle_result_unwrapped.data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_answer).unwrap(),
+            to_binary(&HandleAnswer::Renew { status: Success }).unwrap()
+        );
+
+        // = when the expiry is too large to extend
+        let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
+        let mut alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        alias_object.expires_at = Some(u64::MAX - 1);
+        alias_storage
+            .set_alias(alias.as_bytes(), alias_object)
+            .unwrap();
+        handle(&mut deps, env, receive_msg).unwrap();
+        // = * it caps the expiry
+        let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+        let alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        assert_eq!(alias_object.expires_at, Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_try_create_with_alias_in_grace_period() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: None,
+                native_payments: None,
+                registration_period: Some(100),
+                grace_period: Some(50),
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+        let alias: &str = "nail biter";
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        let created_at: u64 = env.block.time;
+        let create_msg = |from: &str| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        handle(&mut deps, env.clone(), create_msg("crump")).unwrap();
+
+        // = when the alias has expired but is in its grace period
+        env.block.time = created_at + 100;
+        // = * search reports it as expired, in grace
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                current_time: Some(env.block.time),
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("crump"));
+        assert_eq!(val.attributes.expires_at, Some(created_at + 100));
+        assert_eq!(val.grace_ends_at, Some(created_at + 150));
+        assert_eq!(val.status, Some(AliasStatus::ExpiredInGrace));
+        // = * it cannot be claimed by someone else
+        let handle_result = handle(&mut deps, env.clone(), create_msg("frumpette"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasInGracePeriod.to_string());
+        // = * the previous owner can still create another alias
+        handle(
+            &mut deps,
+            env.clone(),
+            HandleMsg::Receive {
+                sender: HumanAddr::from("crump"),
+                from: HumanAddr::from("crump"),
+                amount: mock_fee(),
+                msg: to_binary(&ReceiveMsg::Create {
+                    alias: "toe biter".to_string(),
+                    avatar_url: None,
+                })
+                .unwrap(),
+            },
+        )
+        .unwrap();
+        // = * only the previous owner can renew it
+        let renew_msg = |from: &str| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Renew {
+                alias: alias.to_string(),
+            })
+            .unwrap(),
+        };
+        let handle_result = handle(&mut deps, env.clone(), renew_msg("frumpette"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+        handle(&mut deps, env.clone(), renew_msg("crump")).unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                current_time: Some(env.block.time),
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.expires_at, Some(created_at + 200));
+        assert_eq!(val.grace_ends_at, Some(created_at + 250));
+        assert_eq!(val.status, Some(AliasStatus::Active));
+
+        // = when the grace period is over
+        env.block.time = created_at + 250;
+        // = * it no longer resolves when queried with the current time
+        let search_msg = |current_time: Option<u64>| QueryMsg::Search {
+            search_type: "alias".to_string(),
+            search_value: alias.to_string(),
+            current_time,
+        };
+        let query_result = query(&deps, search_msg(Some(env.block.time)));
+        let error = extract_error_msg(query_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+        let query_result = query(
+            &deps,
+            QueryMsg::ReverseLookup {
+                address: HumanAddr::from("crump"),
+                current_time: Some(env.block.time),
+            },
+        );
+        let error = extract_error_msg(query_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+        // = * it still resolves without the current time or before the grace period is over
+        query(&deps, search_msg(None)).unwrap();
+        query(&deps, search_msg(Some(env.block.time - 1))).unwrap();
+        // = * it can be claimed by someone else
+        handle(&mut deps, env, create_msg("frumpette")).unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("frumpette"));
+
+        // = when the grace period is too long to add to the expiry
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: None,
+                native_payments: None,
+                registration_period: None,
+                grace_period: Some(u64::MAX),
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        env.block.time = created_at + 1_000;
+        // = * the expired alias is never released
+        let handle_result = handle(&mut deps, env, create_msg("crump"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasInGracePeriod.to_string());
+    }
+
+    #[test]
+    fn test_try_create_with_expired_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: None,
+                native_payments: None,
+                registration_period: Some(100),
+                grace_period: None,
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+        let alias: &str = "nail biter";
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        let create_msg = |from: &str| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        handle(&mut deps, env.clone(), create_msg("crump")).unwrap();
+
+        // = when the alias has not expired
+        // = * it cannot be claimed
+        let handle_result = handle(&mut deps, env.clone(), create_msg("frumpette"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasTaken.to_string());
+
+        // = when the alias has expired
+        env.block.time += 100;
+        // = * search returns its expiry until it is claimed
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.expires_at, Some(env.block.time));
+
+        // = * it can be claimed by someone else
+        handle(&mut deps, env.clone(), create_msg("frumpette")).unwrap();
+        let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+        let alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        assert_eq!(alias_object.human_address, HumanAddr::from("frumpette"));
+        // = * the previous owner no longer has an alias
+        let addresses_aliases_storage =
+            AddressesAliasesReadonlyStorage::from_storage(&deps.storage);
+        assert_eq!(
+            addresses_aliases_storage
+                .get_aliases(&"crump".to_string())
+                .unwrap(),
+            None
+        );
+        let query_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "crump".to_string(),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_response);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+    }
+
+    #[test]
+    fn test_try_revoke_sub_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        create_parent_alias_helper(&mut deps);
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::CreateSubAlias {
+                alias: "admin.btn.group".to_string(),
+                address: HumanAddr::from("frump"),
+                avatar_url: None,
+            },
+        )
+        .unwrap();
+        let revoke_msg = HandleMsg::RevokeSubAlias {
+            alias: "admin.btn.group".to_string(),
+        };
+
+        // = when the alias is not a sub-alias
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::RevokeSubAlias {
+                alias: "btn.group".to_string(),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::NotSubAlias.to_string());
+
+        // = when sent by someone other than the parent's owner
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            revoke_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when sent by the parent's owner
+        handle(&mut deps, mock_env(mock_user_address(), &[]), revoke_msg).unwrap();
+        // = * it no longer resolves
+        let query_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "admin.btn.group".to_string(),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_response);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+        // = * it is removed from the address's aliases
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "frump".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "epstein didn't kill himself");
+
+        let create_sub_alias_msg = |alias: &str| HandleMsg::CreateSubAlias {
+            alias: alias.to_string(),
+            address: HumanAddr::from("frump"),
+            avatar_url: None,
+        };
+        let update_sub_alias_msg = |alias: &str| HandleMsg::UpdateSubAlias {
+            alias: alias.to_string(),
+            address: HumanAddr::from("crump"),
+            avatar_url: None,
+        };
+        let create_msg = |from: &str, alias: &str| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        let alias_not_found = |deps: &Extern<MockStorage, MockApi, MockQuerier>, alias: &str| {
+            let query_response = query(
+                deps,
+                QueryMsg::ResolveAlias {
+                    alias: alias.to_string(),
+                    current_time: None,
+                },
+            );
+            extract_error_msg(query_response) == ContractError::AliasNotFound.to_string()
+        };
+
+        // = when the parent alias is destroyed
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            create_sub_alias_msg("admin.btn.group"),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::Destroy {
+                alias: "btn.group".to_string(),
+            },
+        )
+        .unwrap();
+        // = * its sub-aliases are revoked
+        assert!(alias_not_found(&deps, "admin.btn.group"));
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "frump".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "epstein didn't kill himself");
+        // = * whoever registers the parent next cannot take them over
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_msg("crump", "btn.group"),
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("crump"), &[]),
+            update_sub_alias_msg("admin.btn.group"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when the parent alias is renamed
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("crump"), &[]),
+            create_sub_alias_msg("admin.btn.group"),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            HandleMsg::Receive {
+                sender: HumanAddr::from("crump"),
+                from: HumanAddr::from("crump"),
+                amount: mock_fee(),
+                msg: to_binary(&ReceiveMsg::Rename {
+                    from_alias: "btn.group".to_string(),
+                    to_alias: "btn.team".to_string(),
+                })
+                .unwrap(),
+            },
+        )
+        .unwrap();
+        // = * its sub-aliases are revoked
+        assert!(alias_not_found(&deps, "admin.btn.group"));
+        assert!(alias_not_found(&deps, "admin.btn.team"));
+        // = * whoever registers the old name next cannot take them over
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_msg("trump", "btn.group"),
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("trump"), &[]),
+            update_sub_alias_msg("admin.btn.group"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when the parent alias expires and is claimed by someone else
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: None,
+                native_payments: None,
+                registration_period: Some(100),
+                grace_period: None,
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        handle(&mut deps, env.clone(), create_msg("some-geezer", "btn.org")).unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            create_sub_alias_msg("admin.btn.org"),
+        )
+        .unwrap();
+        env.block.time += 100;
+        handle(&mut deps, env, create_msg("crump", "btn.org")).unwrap();
+        // = * its sub-aliases are revoked
+        assert!(alias_not_found(&deps, "admin.btn.org"));
+        // = * the new owner cannot take them over
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("crump"), &[]),
+            update_sub_alias_msg("admin.btn.org"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+    }
+
+    #[test]
+    fn test_try_set_primary() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        let create_msg = |alias: &str| HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_msg("nail biter"),
+        )
+        .unwrap();
+        let aliases_for_frump = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+            let query_result = query(
+                deps,
+                QueryMsg::Aliases {
+                    address: HumanAddr::from("frump"),
+                },
+            )
+            .unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::Aliases { primary, aliases } => (
+                    primary,
+                    aliases
+                        .into_iter()
+                        .map(|alias_attributes| alias_attributes.alias)
+                        .collect::<Vec<String>>(),
+                ),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+
+        // = * it lists all of the address's aliases with the first as primary
+        assert_eq!(
+            aliases_for_frump(&deps),
+            (
+                Some("epstein didn't kill himself".to_string()),
+                vec![
+                    "epstein didn't kill himself".to_string(),
+                    "nail biter".to_string()
+                ]
+            )
+        );
+
+        // = when the alias does not exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::SetPrimary {
+                alias: "toe biter".to_string(),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when sent by someone other than the owner
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetPrimary {
+                alias: "nail biter".to_string(),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when sent by the owner
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::SetPrimary {
+                alias: " Nail Biter ".to_string(),
+            },
+        );
+        // = * it returns a set primary answer
+        let handle_answer: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_answer).unwrap(),
+            to_binary(&HandleAnswer::SetPrimary { status: Success }).unwrap()
+        );
+        // = * search by address returns the new primary
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "frump".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "nail biter");
+
+        // = when the primary is destroyed
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::Destroy {
+                alias: "nail biter".to_string(),
+            },
+        )
+        .unwrap();
+        // = * the oldest remaining alias becomes primary
+        assert_eq!(
+            aliases_for_frump(&deps),
+            (
+                Some("epstein didn't kill himself".to_string()),
+                vec!["epstein didn't kill himself".to_string()]
+            )
+        );
+
+        // = when the last alias is destroyed
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::Destroy {
+                alias: "epstein didn't kill himself".to_string(),
+            },
+        )
+        .unwrap();
+        // = * the address has no aliases
+        assert_eq!(aliases_for_frump(&deps), (None, vec![]));
+    }
+
+    #[test]
+    fn test_try_transfer() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let transfer_msg = HandleMsg::Transfer {
+            alias: alias.to_string(),
+            recipient: mock_user_address(),
+        };
+
+        // = when the alias does not exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::Transfer {
+                alias: "nail biter".to_string(),
+                recipient: mock_user_address(),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when sent by someone other than the owner
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            transfer_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when sent by the owner
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            transfer_msg,
+        );
+        // = * it returns a transfer answer
+        let handle_answer: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_answer).unwrap(),
+            to_binary(&HandleAnswer::Transfer { status: Success }).unwrap()
+        );
+        // = * the alias resolves to the recipient
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, mock_user_address());
+        // = * the recipient resolves to the alias
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: mock_user_address().to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, alias);
+        // = * the previous owner no longer has an alias
+        let query_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "frump".to_string(),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_response);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when the recipient already has an alias
+        let create_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "nail biter".to_string(),
+                avatar_url: None,
+            })
+            .unwrap(),
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_msg,
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::Transfer {
+                alias: alias.to_string(),
+                recipient: HumanAddr::from("frump"),
+            },
+        )
+        .unwrap();
+        // = * it is added to the recipient's aliases without changing the primary
+        let query_result = query(
+            &deps,
+            QueryMsg::Aliases {
+                address: HumanAddr::from("frump"),
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Aliases { primary, aliases } => {
+                assert_eq!(primary, Some("nail biter".to_string()));
+                let aliases: Vec<String> = aliases.into_iter().map(|a| a.alias).collect();
+                assert_eq!(aliases, vec!["nail biter".to_string(), alias.to_string()]);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+    }
+
+    #[test]
+    fn test_try_update_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let avatar_url: &str = "https://www.btn.group/avatar.png";
+        let update_alias_msg = HandleMsg::UpdateAlias {
+            alias: alias.to_string(),
+            avatar_url: Some(avatar_url.to_string()),
+        };
+
+        // = when the alias does not exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::UpdateAlias {
+                alias: "nail biter".to_string(),
+                avatar_url: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when sent by someone other than the owner
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            update_alias_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when the avatar url is invalid
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::UpdateAlias {
+                alias: alias.to_string(),
+                avatar_url: Some("javascript:alert(1)".to_string()),
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::AvatarUrlInvalidScheme.to_string());
+
+        // = when sent by the owner
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            update_alias_msg,
+        );
+        // = * it returns an update alias answer
+        let handle_answer: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_answer).unwrap(),
+            to_binary(&HandleAnswer::UpdateAlias { status: Success }).unwrap()
+        );
+        // = * it updates the avatar url
+        let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+        let alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        assert_eq!(alias_object.avatar_url, Some(avatar_url.to_string()));
+        assert_eq!(alias_object.human_address, HumanAddr::from("frump"));
+
+        // = when the avatar url is cleared
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::UpdateAlias {
+                alias: alias.to_string(),
+                avatar_url: None,
+            },
+        )
+        .unwrap();
+        // = * it removes the avatar url
+        let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
+        let alias_object: Alias = alias_storage.get_alias(alias.as_bytes()).unwrap().unwrap();
+        assert_eq!(alias_object.avatar_url, None);
+    }
+
+    #[test]
+    fn test_try_update_sub_alias() {
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+        create_parent_alias_helper(&mut deps);
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::CreateSubAlias {
+                alias: "admin.btn.group".to_string(),
+                address: HumanAddr::from("frump"),
+                avatar_url: None,
+            },
+        )
+        .unwrap();
+        let update_msg = HandleMsg::UpdateSubAlias {
+            alias: "admin.btn.group".to_string(),
+            address: HumanAddr::from("crump"),
+            avatar_url: Some("https://www.btn.group/avatar.png".to_string()),
+        };
+
+        // = when sent by someone other than the parent's owner
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            update_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when sent by the parent's owner
+        handle(&mut deps, mock_env(mock_user_address(), &[]), update_msg).unwrap();
+        // = * it points the sub-alias at the new address
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "crump".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "admin.btn.group");
+        assert_eq!(
+            val.attributes.avatar_url,
+            Some("https://www.btn.group/avatar.png".to_string())
+        );
+        // = * the previous address no longer holds it
+        let query_result = query(
+            &deps,
+            QueryMsg::Aliases {
+                address: HumanAddr::from("frump"),
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::Aliases { aliases, .. } => assert_eq!(aliases.len(), 1),
+            _ => panic!("Unexpected query answer"),
+        }
+    }
+
+    #[test]
+    fn test_try_update_config() {
+        let new_token = PaymentToken {
+            token: SecretContract {
+                address: HumanAddr::from("sscrt-address"),
+                contract_hash: "sscrt-contract-hash".to_string(),
+            },
+            fee_recipient: HumanAddr::from("sscrt-fee-recipient"),
+            fee: Uint128(5_000_000),
+            length_prices: vec![],
+        };
+        let update_config_msg = HandleMsg::UpdateConfig {
+            payment_tokens: Some(vec![mock_payment_token(), new_token.clone()]),
+            native_payments: None,
+            registration_period: None,
+            grace_period: None,
+            max_batch_size: None,
+        };
+
+        // Initialize
+        let (init_result, mut deps) = init_helper();
+
+        // = * init registers the contract with the payment token
+        assert_eq!(
+            init_result.unwrap().messages,
+            vec![snip20::register_receive_msg(
+                mock_env(mock_user_address(), &[]).contract_code_hash,
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()],
+        );
+
+        // = when called by someone other than the admin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            update_config_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
+
+        // = when called by the admin
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            update_config_msg,
+        );
+        let handle_result_unwrapped = handle_result.unwrap();
+
+        // = * it registers the contract with every payment token
+        assert_eq!(
+            handle_result_unwrapped.messages,
+            vec![
+                snip20::register_receive_msg(
+                    mock_env(mock_user_address(), &[]).contract_code_hash,
+                    None,
+                    BLOCK_SIZE,
+                    mock_buttcoin().contract_hash,
+                    mock_buttcoin().address,
+                )
+                .unwrap(),
+                snip20::register_receive_msg(
+                    mock_env(mock_user_address(), &[]).contract_code_hash,
+                    None,
+                    BLOCK_SIZE,
+                    new_token.token.contract_hash.clone(),
+                    new_token.token.address.clone(),
+                )
+                .unwrap()
+            ],
+        );
+
+        // = * it updates the config
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.admin, mock_user_address());
+        assert_eq!(
+            config.payment_tokens,
+            vec![mock_payment_token(), new_token.clone()]
+        );
+
+        // = * it accepts the new token at its own fee and sends it to its own fee recipient
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
             avatar_url: None,
         };
         let receive_msg = HandleMsg::Receive {
             sender: mock_user_address(),
             from: mock_user_address(),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            amount: mock_fee(),
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(new_token.token.address.clone(), &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            ContractError::WrongAmount {
+                sent: Uint128(1000000),
+                required: Uint128(5000000),
+            }
+            .to_string()
+        );
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: new_token.fee,
+            msg: to_binary(&create_alias_message).unwrap(),
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(new_token.token.address.clone(), &[]),
+            receive_msg,
+        );
+        assert_eq!(
+            handle_result.unwrap().messages,
+            vec![snip20::transfer_msg(
+                new_token.fee_recipient,
+                new_token.fee,
+                None,
+                BLOCK_SIZE,
+                new_token.token.contract_hash,
+                new_token.token.address,
+            )
+            .unwrap()],
+        );
+
+        // = when a token is removed from the payment tokens
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: Some(vec![]),
+                native_payments: None,
+                registration_period: None,
+                grace_period: None,
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
+
+        // = * it is no longer accepted
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nail biter".to_string(),
+            avatar_url: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("crump"),
+            from: HumanAddr::from("crump"),
+            amount: mock_fee(),
             msg: to_binary(&create_alias_message).unwrap(),
         };
         let handle_result = handle(
             &mut deps,
             mock_env(mock_buttcoin().address, &[]),
-            receive_msg.clone(),
+            receive_msg,
         );
-        handle_result.unwrap();
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::Unauthorized.to_string());
 
-        // = when user tries to delete an alias that does not exist
+        // = when the max batch size is set to 0
         // = * it raises an error
-        let destroy_alias_message = HandleMsg::Destroy {
-            alias: "idonotexist".to_string(),
-        };
-        let res = handle(
+        let handle_result = handle(
             &mut deps,
             mock_env(mock_user_address(), &[]),
-            destroy_alias_message,
+            HandleMsg::UpdateConfig {
+                payment_tokens: None,
+                native_payments: None,
+                registration_period: None,
+                grace_period: None,
+                max_batch_size: Some(0),
+            },
         );
-        let error = extract_error_msg(res);
-        assert_eq!(error, "Alias not found");
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, ContractError::InvalidMaxBatchSize.to_string());
 
-        // = when user tries to delete an alias that belongs to someone else
+        // = when initialized with a max batch size of 0
         // = * it raises an error
-        let destroy_alias_message = HandleMsg::Destroy {
-            alias: alias.to_string(),
+        let mut deps = mock_dependencies(20, &[]);
+        let init_msg = InitMsg {
+            aliases: vec![],
+            native_payments: vec![],
+            payment_tokens: vec![],
+            registration_period: 0,
+            grace_period: 0,
+            max_batch_size: 0,
         };
-        let res = handle(
+        let init_result = init(&mut deps, mock_env(mock_user_address(), &[]), init_msg);
+        let error = extract_error_msg(init_result);
+        assert_eq!(error, ContractError::InvalidMaxBatchSize.to_string());
+    }
+
+    #[test]
+    fn test_contract_error() {
+        // = when a contract error is returned
+        // = * its message starts with a stable code
+        let error: StdError = ContractError::WrongAmount {
+            sent: Uint128(1),
+            required: Uint128(2),
+        }
+        .into();
+        assert_eq!(
+            extract_error_msg::<()>(Err(error)),
+            "401: Amount sent in: 1. Amount required 2."
+        );
+        for (contract_error, code) in &[
+            (ContractError::AliasTaken, 101),
+            (ContractError::AliasTooLong, 202),
+            (
+                ContractError::AliasInvalidCharacter { character: '\n' },
+                203,
+            ),
+            (ContractError::InvalidSearchType, 601),
+        ] {
+            assert_eq!(contract_error.code(), *code);
+            assert!(contract_error
+                .to_string()
+                .starts_with(&format!("{}: ", code)));
+        }
+        assert_eq!(
+            ContractError::AliasInvalidCharacter { character: '\n' }.to_string(),
+            "203: Alias contains invalid character '\\n'"
+        );
+    }
+
+    #[test]
+    fn test_corrupt_storage() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        PrefixedStorage::new(ALIASES_PREFIX, &mut deps.storage).set(alias.as_bytes(), b"corrupt");
+        PrefixedStorage::new(ADDRESSES_ALIASES_PREFIX, &mut deps.storage).set(b"frump", b"corrupt");
+
+        // = when an alias is corrupt
+        // = * handling it returns a parse error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::UpdateAlias {
+                alias: alias.to_string(),
+                avatar_url: None,
+            },
+        );
+        match handle_result {
+            Err(StdError::ParseErr { .. }) => {}
+            _ => panic!("Expected a parse error"),
+        }
+        let handle_result = handle(
             &mut deps,
             mock_env(mock_buttcoin().address, &[]),
-            destroy_alias_message,
+            create_alias_msg_helper("crump", alias),
         );
-        let error = extract_error_msg(res);
-        assert_eq!(error, "Unauthorized");
+        match handle_result {
+            Err(StdError::ParseErr { .. }) => {}
+            _ => panic!("Expected a parse error"),
+        }
+        // = * querying it returns a parse error
+        let query_result = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                current_time: None,
+            },
+        );
+        match query_result {
+            Err(StdError::ParseErr { .. }) => {}
+            _ => panic!("Expected a parse error"),
+        }
 
-        // = when user destroy their own alias
-        // = * alias is removed
-        let destroy_alias_message = HandleMsg::Destroy {
-            alias: alias.to_string(),
-        };
+        // = when an address's aliases are corrupt
+        // = * querying them returns a parse error
+        let query_result = query(
+            &deps,
+            QueryMsg::Aliases {
+                address: HumanAddr::from("frump"),
+            },
+        );
+        match query_result {
+            Err(StdError::ParseErr { .. }) => {}
+            _ => panic!("Expected a parse error"),
+        }
+    }
+
+    // === QUERY TESTS ===
+
+    #[test]
+    fn test_query_search() {
+        let (_init_result, deps) = init_helper();
+
+        // = when search type is not address or alias
+        // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "avatar_url".to_string(),
+                search_value: "https://www.btn.group".to_string(),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_result);
+        assert_eq!(error, ContractError::InvalidSearchType.to_string());
+
+        // = when searching by address or alias
+        // = * it returns the alias as before
+        for (search_type, search_value) in &[
+            ("address", "frump"),
+            ("alias", "Epstein didn't kill himself"),
+        ] {
+            let query_result = query(
+                &deps,
+                QueryMsg::Search {
+                    search_type: search_type.to_string(),
+                    search_value: search_value.to_string(),
+                    current_time: None,
+                },
+            )
+            .unwrap();
+            let search_response: SearchResponse = from_binary(&query_result).unwrap();
+            assert_eq!(
+                search_response,
+                SearchResponse {
+                    r#type: "aliases".to_string(),
+                    attributes: AliasAttributes {
+                        alias: "epstein didn't kill himself".to_string(),
+                        avatar_url: None,
+                        address: HumanAddr::from("frump"),
+                        expires_at: None,
+                    },
+                    grace_ends_at: None,
+                    status: None,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_query_resolve_alias() {
+        let (_init_result, deps) = init_helper();
+
+        // = when the alias does not exist
+        // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::ResolveAlias {
+                alias: "nail biter".to_string(),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when the alias exists
+        // = * it returns the alias's address and attributes
+        let query_result = query(
+            &deps,
+            QueryMsg::ResolveAlias {
+                alias: "Epstein didn't kill himself ".to_string(),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let resolve_alias_response: ResolveAliasResponse = from_binary(&query_result).unwrap();
+        assert_eq!(
+            resolve_alias_response,
+            ResolveAliasResponse {
+                alias: "epstein didn't kill himself".to_string(),
+                address: HumanAddr::from("frump"),
+                avatar_url: None,
+                expires_at: None,
+                grace_ends_at: None,
+                status: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_query_reverse_lookup() {
+        let (_init_result, mut deps) = init_helper();
+
+        // = when the address does not have an alias
+        // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::ReverseLookup {
+                address: HumanAddr::from("crump"),
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_result);
+        assert_eq!(error, ContractError::AliasNotFound.to_string());
+
+        // = when the address has several aliases
+        // = * it returns the primary alias
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_alias_msg_helper("frump", "nail biter"),
+        )
+        .unwrap();
+        let query_result = query(
+            &deps,
+            QueryMsg::ReverseLookup {
+                address: HumanAddr::from("frump"),
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let reverse_lookup_response: ReverseLookupResponse = from_binary(&query_result).unwrap();
+        assert_eq!(
+            reverse_lookup_response,
+            ReverseLookupResponse {
+                address: HumanAddr::from("frump"),
+                alias: "epstein didn't kill himself".to_string(),
+                avatar_url: None,
+                expires_at: None,
+                grace_ends_at: None,
+                status: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_query_batch_resolve() {
+        let (_init_result, mut deps) = init_helper();
+
+        // = when given aliases
+        // = * it returns one result per alias with null for misses
+        let query_result = query(
+            &deps,
+            QueryMsg::BatchResolve {
+                aliases: vec![
+                    "nail biter".to_string(),
+                    "Epstein didn't kill himself".to_string(),
+                ],
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let batch_resolve_response: BatchResolveResponse = from_binary(&query_result).unwrap();
+        assert_eq!(
+            batch_resolve_response.results,
+            vec![
+                None,
+                Some(ResolveAliasResponse {
+                    alias: "epstein didn't kill himself".to_string(),
+                    address: HumanAddr::from("frump"),
+                    avatar_url: None,
+                    expires_at: None,
+                    grace_ends_at: None,
+                    status: None,
+                })
+            ]
+        );
+
+        // = when given more aliases than the max batch size
+        // = * it raises an error
         handle(
             &mut deps,
             mock_env(mock_user_address(), &[]),
-            destroy_alias_message,
+            HandleMsg::UpdateConfig {
+                payment_tokens: None,
+                native_payments: None,
+                registration_period: None,
+                grace_period: None,
+                max_batch_size: Some(1),
+            },
         )
         .unwrap();
-        // Query destroyed alias via alias
-        let query_response = query(
-            &mut deps,
-            QueryMsg::Search {
-                search_type: "alias".to_string(),
-                search_value: alias.to_string(),
+        let query_result = query(
+            &deps,
+            QueryMsg::BatchResolve {
+                aliases: vec!["nail biter".to_string(), "batman".to_string()],
+                current_time: None,
             },
         );
-        let error = extract_error_msg(query_response);
-        assert_eq!(error, "Alias not found");
-        // Query destroyed alias via address
-        let query_response = query(
-            &mut deps,
-            QueryMsg::Search {
-                search_type: "address".to_string(),
-                search_value: mock_user_address().to_string(),
-            },
+        let error = extract_error_msg(query_result);
+        assert_eq!(
+            error,
+            ContractError::BatchTooLarge { max_batch_size: 1 }.to_string()
         );
-        let error = extract_error_msg(query_response);
-        assert_eq!(error, "Alias not found");
     }
 
     #[test]
-    fn test_try_create() {
-        let alias = "   nail biter    ";
-        let avatar_url = "https://www.btn.group";
+    fn test_query_batch_reverse_lookup() {
+        let (_init_result, deps) = init_helper();
 
-        // Initialize
-        let (_init_result, mut deps) = init_helper();
+        // = when given addresses
+        // = * it returns one result per address with null for misses
+        let query_result = query(
+            &deps,
+            QueryMsg::BatchReverseLookup {
+                addresses: vec![HumanAddr::from("frump"), HumanAddr::from("crump")],
+                current_time: None,
+            },
+        )
+        .unwrap();
+        let batch_reverse_lookup_response: BatchReverseLookupResponse =
+            from_binary(&query_result).unwrap();
+        assert_eq!(
+            batch_reverse_lookup_response.results,
+            vec![
+                Some(ReverseLookupResponse {
+                    address: HumanAddr::from("frump"),
+                    alias: "epstein didn't kill himself".to_string(),
+                    avatar_url: None,
+                    expires_at: None,
+                    grace_ends_at: None,
+                    status: None,
+                }),
+                None
+            ]
+        );
 
-        // when creating an alias
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_string(),
-            avatar_url: Some(avatar_url.to_string()),
+        // = when given more addresses than the max batch size
+        // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::BatchReverseLookup {
+                addresses: vec![HumanAddr::from("frump"); 11],
+                current_time: None,
+            },
+        );
+        let error = extract_error_msg(query_result);
+        assert_eq!(
+            error,
+            ContractError::BatchTooLarge { max_batch_size: 10 }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_query_search_prefix() {
+        let (_init_result, mut deps) = init_helper();
+        for alias in &["btn.group admin2", "btn.group admin", "batman", "btn.group"] {
+            handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                create_alias_msg_helper("crump", alias),
+            )
+            .unwrap();
+        }
+        let search_prefix_helper = |deps: &Extern<MockStorage, MockApi, MockQuerier>,
+                                    prefix: &str,
+                                    limit: Option<u16>|
+         -> Vec<AliasAttributes> {
+            let query_result = query(
+                deps,
+                QueryMsg::SearchPrefix {
+                    prefix: prefix.to_string(),
+                    limit,
+                },
+            )
+            .unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::SearchPrefix { aliases } => aliases,
+                _ => panic!("Unexpected query answer"),
+            }
         };
-        let receive_msg = HandleMsg::Receive {
-            sender: mock_user_address(),
-            from: mock_user_address(),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message).unwrap(),
+        let aliases_helper = |aliases: Vec<AliasAttributes>| -> Vec<String> {
+            aliases
+                .into_iter()
+                .map(|alias_attributes| alias_attributes.alias)
+                .collect()
         };
 
-        // = when user sends in a token that is not buttcoin
-        // = * it raises an error
-        let handle_result = handle(
-            &mut deps,
-            mock_env(mock_user_address(), &[]),
-            receive_msg.clone(),
+        // = when aliases start with the prefix
+        // = * it returns them in lexical order with their attributes
+        let aliases = search_prefix_helper(&deps, "BTN.", None);
+        assert_eq!(
+            aliases[0],
+            AliasAttributes {
+                alias: "btn.group".to_string(),
+                avatar_url: None,
+                address: HumanAddr::from("crump"),
+                expires_at: None,
+            }
         );
         assert_eq!(
-            handle_result.unwrap_err(),
-            StdError::Unauthorized { backtrace: None }
+            aliases_helper(aliases),
+            vec!["btn.group", "btn.group admin", "btn.group admin2"]
         );
-
-        // = when user sends in buttcoin
-        let handle_result = handle(
-            &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            receive_msg.clone(),
+        // = * it returns at most limit aliases
+        assert_eq!(
+            aliases_helper(search_prefix_helper(&deps, "b", Some(2))),
+            vec!["batman", "btn.group"]
         );
-        let handle_result_unwrapped = handle_result.unwrap();
-
-        // = * it sends the BUTT to the BUTT lode
+        // = * an empty prefix matches every alias
         assert_eq!(
-            handle_result_unwrapped.messages,
-            vec![snip20::transfer_msg(
-                mock_butt_lode().address,
-                Uint128(AMOUNT_FOR_TRANSACTION),
-                None,
-                BLOCK_SIZE,
-                mock_buttcoin().contract_hash,
-                mock_buttcoin().address,
+            aliases_helper(search_prefix_helper(&deps, "", None)),
+            vec![
+                "batman",
+                "btn.group",
+                "btn.group admin",
+                "btn.group admin2",
+                "epstein didn't kill himself"
+            ]
+        );
+
+        // = when no alias starts with the prefix
+        // = * it returns nothing
+        assert!(search_prefix_helper(&deps, "robin", None).is_empty());
+
+        // = when aliases are destroyed
+        // = * they are no longer returned
+        for alias in &["btn.group admin", "btn.group admin2"] {
+            handle(
+                &mut deps,
+                mock_env(HumanAddr::from("crump"), &[]),
+                HandleMsg::Destroy {
+                    alias: alias.to_string(),
+                },
             )
-            .unwrap()],
+            .unwrap();
+        }
+        assert_eq!(
+            aliases_helper(search_prefix_helper(&deps, "btn.", None)),
+            vec!["btn.group"]
+        );
+        assert!(search_prefix_helper(&deps, "btn.group ", None).is_empty());
+        // = * the prefixes that only led to them are removed
+        let aliases_trie_storage = AliasesTrieReadonlyStorage::from_storage(&deps.storage);
+        assert_eq!(aliases_trie_storage.get_node(b"btn.group ").unwrap(), None);
+        assert_eq!(
+            aliases_trie_storage.get_node(b"btn.group").unwrap(),
+            Some(AliasesTrieNode {
+                children: vec![],
+                is_alias: true,
+            })
         );
+    }
 
-        // = * It creates alias without trailing and leading whitespaces
-        let search_response = query(
+    #[test]
+    fn test_query_config() {
+        let (_init_result, deps) = init_helper();
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        let query_result = query(&deps, QueryMsg::Config {}).unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Config {
+                admin,
+                pending_admin,
+                payment_tokens,
+                native_payments,
+                registration_period,
+                grace_period,
+                max_batch_size,
+            } => {
+                assert_eq!(admin, config.admin);
+                assert_eq!(pending_admin, config.pending_admin);
+                assert_eq!(payment_tokens, config.payment_tokens);
+                assert_eq!(native_payments, config.native_payments);
+                assert_eq!(registration_period, config.registration_period);
+                assert_eq!(grace_period, config.grace_period);
+                assert_eq!(max_batch_size, config.max_batch_size);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+    }
+
+    #[test]
+    fn test_query_list_aliases() {
+        let (_init_result, mut deps) = init_helper();
+        for alias in &["nail biter", "batman", "joker", "penguin"] {
+            handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                create_alias_msg_helper("crump", alias),
+            )
+            .unwrap();
+        }
+        handle(
             &mut deps,
-            QueryMsg::Search {
-                search_type: "alias".to_string(),
-                search_value: "nail biter".to_string(),
+            mock_env(HumanAddr::from("crump"), &[]),
+            HandleMsg::Destroy {
+                alias: "nail biter".to_string(),
             },
         )
         .unwrap();
-        let val: SearchResponse = from_binary(&search_response).unwrap();
-        assert_eq!(mock_user_address(), val.attributes.clone().address);
+        let list_aliases_helper = |deps: &Extern<MockStorage, MockApi, MockQuerier>,
+                                   start_after: Option<u64>,
+                                   limit: Option<u16>|
+         -> (Vec<String>, Option<u64>) {
+            let query_result = query(deps, QueryMsg::ListAliases { start_after, limit }).unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::ListAliases {
+                    aliases,
+                    next_start_after,
+                } => (
+                    aliases
+                        .into_iter()
+                        .map(|alias_attributes| alias_attributes.alias)
+                        .collect(),
+                    next_start_after,
+                ),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+
+        // = when listing from the start
+        // = * it returns the aliases in the order they were registered, leaving out destroyed ones
         assert_eq!(
-            avatar_url,
-            val.attributes.clone().avatar_url.unwrap().to_string()
+            list_aliases_helper(&deps, None, None),
+            (
+                vec![
+                    "epstein didn't kill himself".to_string(),
+                    "batman".to_string(),
+                    "joker".to_string(),
+                    "penguin".to_string()
+                ],
+                None
+            )
         );
-        let search_response = query(
+        // = * a page covers limit ids and points at the next page
+        assert_eq!(
+            list_aliases_helper(&deps, None, Some(2)),
+            (vec!["epstein didn't kill himself".to_string()], Some(1))
+        );
+
+        // = when an alias is destroyed and another renamed between pages
+        handle(
             &mut deps,
-            QueryMsg::Search {
-                search_type: "address".to_string(),
-                search_value: mock_user_address().to_string(),
+            mock_env(HumanAddr::from("crump"), &[]),
+            HandleMsg::Destroy {
+                alias: "batman".to_string(),
             },
         )
         .unwrap();
-        let val: SearchResponse = from_binary(&search_response).unwrap();
-        assert_eq!("nail biter", val.attributes.clone().alias.to_string());
-        assert_eq!(mock_user_address(), val.attributes.clone().address);
-        assert_eq!(
-            avatar_url,
-            val.attributes.clone().avatar_url.unwrap().to_string()
-        );
-
-        // = when alias already exists
-        let create_alias_message_two = ReceiveMsg::Create {
-            alias: "Epstein didn't kill himself".to_string(),
-            avatar_url: Some(avatar_url.to_string()),
-        };
-        let receive_msg_two = HandleMsg::Receive {
+        let receive_msg = HandleMsg::Receive {
             sender: HumanAddr::from("crump"),
             from: HumanAddr::from("crump"),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message_two).unwrap(),
+            amount: mock_fee(),
+            msg: to_binary(&ReceiveMsg::Rename {
+                from_alias: "joker".to_string(),
+                to_alias: "robin".to_string(),
+            })
+            .unwrap(),
         };
-        let handle_result = handle(
+        handle(
             &mut deps,
             mock_env(mock_buttcoin().address, &[]),
-            receive_msg_two,
+            receive_msg,
+        )
+        .unwrap();
+        // = * the next page carries on where the last one ended
+        assert_eq!(
+            list_aliases_helper(&deps, Some(1), Some(2)),
+            (vec!["robin".to_string()], Some(3))
+        );
+        // = * the renamed alias keeps its place in the list
+        assert_eq!(
+            list_aliases_helper(&deps, Some(3), Some(2)),
+            (vec!["penguin".to_string()], None)
+        );
+        assert_eq!(
+            list_aliases_helper(&deps, None, None),
+            (
+                vec![
+                    "epstein didn't kill himself".to_string(),
+                    "robin".to_string(),
+                    "penguin".to_string()
+                ],
+                None
+            )
         );
 
-        // = * it raises an error
-        let error = extract_error_msg(handle_result);
-        assert_eq!(error, "Alias has already been taken");
+        // = when listing past the end
+        // = * it returns nothing
+        assert_eq!(list_aliases_helper(&deps, Some(4), None), (vec![], None));
+        assert_eq!(
+            list_aliases_helper(&deps, Some(u64::MAX), None),
+            (vec![], None)
+        );
 
-        // = when alias exists but is a different case
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_uppercase().to_string(),
-            avatar_url: None,
-        };
-        let receive_msg = HandleMsg::Receive {
-            sender: mock_user_address(),
-            from: mock_user_address(),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message).unwrap(),
-        };
+        // = when the limit is 0
+        // = * a page still covers one id so that the cursor moves on
+        assert_eq!(
+            list_aliases_helper(&deps, None, Some(0)),
+            (vec!["epstein didn't kill himself".to_string()], Some(0))
+        );
+        assert_eq!(
+            list_aliases_helper(&deps, Some(0), Some(0)),
+            (vec![], Some(1))
+        );
+    }
 
-        // = * it raises an error
-        let response = handle(
+    #[test]
+    fn test_query_lookalike() {
+        let (_init_result, mut deps) = init_helper();
+        handle(
             &mut deps,
             mock_env(mock_buttcoin().address, &[]),
-            receive_msg,
-        );
-        let error = extract_error_msg(response);
-        assert_eq!(error, "Alias has already been taken");
+            create_alias_msg_helper("frump", "paypal"),
+        )
+        .unwrap();
 
-        // = when alias is too long
-        let alias = "Epstein didn't kill himself".repeat(20);
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_uppercase().to_string(),
-            avatar_url: None,
-        };
-        let receive_msg = HandleMsg::Receive {
-            sender: mock_user_address(),
-            from: mock_user_address(),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message).unwrap(),
-        };
+        // = when a lookalike of a registered alias is queried
+        // = * it returns the registered alias
+        for lookalike in &["paypal", "PAYPA1", "\u{440}\u{430}\u{443}\u{440}\u{430}l"] {
+            let query_result = query(
+                &deps,
+                QueryMsg::Lookalike {
+                    alias: lookalike.to_string(),
+                },
+            )
+            .unwrap();
+            let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+            match query_answer {
+                QueryAnswer::Lookalike { alias } => assert_eq!(alias, Some("paypal".to_string())),
+                _ => panic!("Unexpected query answer"),
+            }
+        }
 
-        // = * it raises an error
-        let response = handle(
-            &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            receive_msg,
-        );
-        let error = extract_error_msg(response);
-        assert_eq!(error, "Alias is too long");
+        // = when nothing registered looks like it
+        // = * it returns nothing
+        let query_result = query(
+            &deps,
+            QueryMsg::Lookalike {
+                alias: "batman".to_string(),
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Lookalike { alias } => assert_eq!(alias, None),
+            _ => panic!("Unexpected query answer"),
+        }
+    }
 
-        // = when user already has an alias
-        // = * it raises an error
-        let alias = "Epstein didn't kill himself".repeat(5);
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_string(),
-            avatar_url: None,
-        };
-        let receive_msg = HandleMsg::Receive {
-            sender: HumanAddr::from("frump"),
-            from: HumanAddr::from("frump"),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message).unwrap(),
-        };
-        let response = handle(
+    #[test]
+    fn test_query_price() {
+        let (_init_result, mut deps) = init_helper();
+        handle(
             &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            receive_msg,
-        );
-        let error = extract_error_msg(response);
-        assert_eq!(error, "Address already has an alias");
-    }
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UpdateConfig {
+                payment_tokens: Some(vec![PaymentToken {
+                    length_prices: mock_length_prices(),
+                    ..mock_payment_token()
+                }]),
+                native_payments: None,
+                registration_period: None,
+                grace_period: None,
+                max_batch_size: None,
+            },
+        )
+        .unwrap();
 
-    // === QUERY TESTS ===
+        // = when alias length has a price
+        // = * it returns the price for the formatted alias
+        let query_result = query(
+            &deps,
+            QueryMsg::Price {
+                alias: "  X ".to_string(),
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Price {
+                alias,
+                prices,
+                native_prices,
+            } => {
+                assert_eq!(alias, "x");
+                assert_eq!(
+                    prices,
+                    vec![TokenPrice {
+                        token: mock_buttcoin(),
+                        fee: Uint128(100_000_000)
+                    }]
+                );
+                assert_eq!(native_prices, coins(1_000_000, "uscrt"));
+            }
+            _ => panic!("Unexpected query answer"),
+        }
 
-    #[test]
-    fn test_query_config() {
-        let (_init_result, deps) = init_helper();
-        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
-        let query_result = query(&deps, QueryMsg::Config {}).unwrap();
+        // = when alias length does not have a price
+        // = * it returns the fee
+        let query_result = query(
+            &deps,
+            QueryMsg::Price {
+                alias: "batman".to_string(),
+            },
+        )
+        .unwrap();
         let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
         match query_answer {
-            QueryAnswer::Config {
-                buttcoin,
-                butt_lode,
+            QueryAnswer::Price {
+                alias,
+                prices,
+                native_prices,
             } => {
-                assert_eq!(buttcoin, config.buttcoin);
-                assert_eq!(butt_lode, config.butt_lode);
+                assert_eq!(alias, "batman");
+                assert_eq!(
+                    prices,
+                    vec![TokenPrice {
+                        token: mock_buttcoin(),
+                        fee: mock_fee()
+                    }]
+                );
+                assert_eq!(native_prices, coins(1_000_000, "uscrt"));
             }
+            _ => panic!("Unexpected query answer"),
         }
     }
 }
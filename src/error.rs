@@ -0,0 +1,176 @@
+use crate::validation::{AVATAR_URL_MAX_LENGTH, AVATAR_URL_SCHEMES};
+use cosmwasm_std::{StdError, Uint128};
+use std::fmt;
+
+// Errors raised by this contract. They reach clients as generic errors whose message
+// starts with the variant's code, e.g. "101: Alias has already been taken", so that
+// clients can branch on the code rather than the wording.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContractError {
+    // Alias availability
+    AliasTaken,
+    AliasInGracePeriod,
+    AliasReserved,
+    AliasAlreadyReserved,
+    AliasTooSimilar { existing: String },
+    AliasDoesNotExpire,
+    AliasesDoNotExpire,
+    AliasNotFound,
+    ReservedAliasNotFound,
+    // Alias and avatar validation
+    AliasTooShort,
+    AliasTooLong,
+    AliasInvalidCharacter { character: char },
+    AliasSurroundingSpace,
+    AliasRepeatedSpaces,
+    AvatarUrlTooLong,
+    AvatarUrlInvalidCharacter,
+    AvatarUrlInvalidScheme,
+    AvatarUrlMissingLocation,
+    // Sub-aliases
+    InvalidSubAlias,
+    NotParentOwner { parent: String },
+    ManagedByParent,
+    NotSubAlias,
+    ParentAliasNotFound,
+    // Payments
+    WrongAmount { sent: Uint128, required: Uint128 },
+    InvalidNativePayment,
+    DenomNotAccepted { denom: String },
+    // Admin
+    NoAdminProposed,
+    // Queries
+    InvalidSearchType,
+    BatchTooLarge { max_batch_size: u16 },
+    InvalidMaxBatchSize,
+    // Authorization
+    Unauthorized,
+}
+
+impl ContractError {
+    // Codes are part of the public interface, never reuse or renumber them
+    pub fn code(&self) -> u16 {
+        match self {
+            ContractError::AliasTaken => 101,
+            ContractError::AliasInGracePeriod => 102,
+            ContractError::AliasReserved => 103,
+            ContractError::AliasAlreadyReserved => 104,
+            ContractError::AliasTooSimilar { .. } => 105,
+            ContractError::AliasDoesNotExpire => 106,
+            ContractError::AliasesDoNotExpire => 107,
+            ContractError::AliasNotFound => 108,
+            ContractError::ReservedAliasNotFound => 109,
+            ContractError::AliasTooShort => 201,
+            ContractError::AliasTooLong => 202,
+            ContractError::AliasInvalidCharacter { .. } => 203,
+            ContractError::AliasSurroundingSpace => 204,
+            ContractError::AliasRepeatedSpaces => 205,
+            ContractError::AvatarUrlTooLong => 211,
+            ContractError::AvatarUrlInvalidCharacter => 212,
+            ContractError::AvatarUrlInvalidScheme => 213,
+            ContractError::AvatarUrlMissingLocation => 214,
+            ContractError::InvalidSubAlias => 301,
+            ContractError::NotParentOwner { .. } => 302,
+            ContractError::ManagedByParent => 303,
+            ContractError::NotSubAlias => 304,
+            ContractError::ParentAliasNotFound => 305,
+            ContractError::WrongAmount { .. } => 401,
+            ContractError::InvalidNativePayment => 402,
+            ContractError::DenomNotAccepted { .. } => 403,
+            ContractError::NoAdminProposed => 501,
+            ContractError::InvalidSearchType => 601,
+            ContractError::BatchTooLarge { .. } => 602,
+            ContractError::InvalidMaxBatchSize => 603,
+            ContractError::Unauthorized => 701,
+        }
+    }
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.code())?;
+        match self {
+            ContractError::AliasTaken => write!(f, "Alias has already been taken"),
+            ContractError::AliasInGracePeriod => write!(
+                f,
+                "Alias has expired but is in its grace period, only the previous owner can renew it"
+            ),
+            ContractError::AliasReserved => write!(f, "Alias is reserved"),
+            ContractError::AliasAlreadyReserved => write!(f, "Alias is already reserved"),
+            ContractError::AliasTooSimilar { existing } => {
+                write!(f, "Alias is too similar to {}", existing)
+            }
+            ContractError::AliasDoesNotExpire => write!(f, "Alias does not expire"),
+            ContractError::AliasesDoNotExpire => write!(f, "Aliases do not expire"),
+            ContractError::AliasNotFound => write!(f, "Alias not found"),
+            ContractError::ReservedAliasNotFound => write!(f, "Reserved alias not found"),
+            ContractError::AliasTooShort => write!(f, "Alias is too short"),
+            ContractError::AliasTooLong => write!(f, "Alias is too long"),
+            ContractError::AliasInvalidCharacter { character } => {
+                write!(f, "Alias contains invalid character {:?}", character)
+            }
+            ContractError::AliasSurroundingSpace => {
+                write!(f, "Alias can not start or end with a space")
+            }
+            ContractError::AliasRepeatedSpaces => {
+                write!(f, "Alias can not contain repeated spaces")
+            }
+            ContractError::AvatarUrlTooLong => write!(
+                f,
+                "Avatar URL can not be longer than {} bytes",
+                AVATAR_URL_MAX_LENGTH
+            ),
+            ContractError::AvatarUrlInvalidCharacter => write!(
+                f,
+                "Avatar URL can not contain whitespace or control characters"
+            ),
+            ContractError::AvatarUrlInvalidScheme => write!(
+                f,
+                "Avatar URL must start with one of {}://",
+                AVATAR_URL_SCHEMES.join("://, ")
+            ),
+            ContractError::AvatarUrlMissingLocation => {
+                write!(f, "Avatar URL is missing a location")
+            }
+            ContractError::InvalidSubAlias => write!(
+                f,
+                "Sub-alias must be a label followed by a dot and its parent alias"
+            ),
+            ContractError::NotParentOwner { parent } => {
+                write!(f, "Only the owner of {} can create its sub-aliases", parent)
+            }
+            ContractError::ManagedByParent => write!(
+                f,
+                "Sub-aliases are managed by the owner of their parent alias"
+            ),
+            ContractError::NotSubAlias => write!(f, "Alias is not a sub-alias"),
+            ContractError::ParentAliasNotFound => write!(f, "Parent alias not found"),
+            ContractError::WrongAmount { sent, required } => {
+                write!(f, "Amount sent in: {}. Amount required {}.", sent, required)
+            }
+            ContractError::InvalidNativePayment => {
+                write!(f, "Payment must be sent in one accepted native denom")
+            }
+            ContractError::DenomNotAccepted { denom } => {
+                write!(f, "{} is not an accepted denom", denom)
+            }
+            ContractError::NoAdminProposed => write!(f, "No admin has been proposed"),
+            ContractError::InvalidSearchType => {
+                write!(f, "search_type must be address or alias")
+            }
+            ContractError::BatchTooLarge { max_batch_size } => {
+                write!(f, "Batch queries accept at most {} inputs", max_batch_size)
+            }
+            ContractError::InvalidMaxBatchSize => write!(f, "Max batch size must be at least 1"),
+            ContractError::Unauthorized => {
+                write!(f, "Sender is not allowed to perform this action")
+            }
+        }
+    }
+}
+
+impl From<ContractError> for StdError {
+    fn from(error: ContractError) -> Self {
+        StdError::generic_err(error.to_string())
+    }
+}
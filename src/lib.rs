@@ -1,7 +1,10 @@
 mod authorize;
+mod confusables;
 pub mod contract;
+pub mod error;
 pub mod msg;
 pub mod state;
+mod validation;
 
 #[cfg(target_arch = "wasm32")]
 mod wasm {
@@ -1,5 +1,5 @@
-use crate::state::SecretContract;
-use cosmwasm_std::{Binary, HumanAddr, Uint128};
+use crate::state::{NativePayment, PaymentToken, ReservedAlias, SecretContract};
+use cosmwasm_std::{Binary, Coin, HumanAddr, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -9,59 +9,249 @@ pub struct AliasAttributes {
     pub alias: String,
     pub avatar_url: Option<String>,
     pub address: HumanAddr,
+    pub expires_at: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InitMsg {
-    pub buttcoin: SecretContract,
-    pub butt_lode: SecretContract,
+    pub native_payments: Vec<NativePayment>,
+    pub payment_tokens: Vec<PaymentToken>,
+    pub registration_period: u64,
+    pub grace_period: u64,
+    pub max_batch_size: u16,
     pub aliases: Vec<AliasAttributes>,
 }
 
 // We define a custom struct for each query response
+// One result per alias in the query, null when it does not resolve
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchResolveResponse {
+    pub results: Vec<Option<ResolveAliasResponse>>,
+}
+
+// One result per address in the query, null when it has no alias
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchReverseLookupResponse {
+    pub results: Vec<Option<ReverseLookupResponse>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolveAliasResponse {
+    pub alias: String,
+    pub address: HumanAddr,
+    pub avatar_url: Option<String>,
+    pub expires_at: Option<u64>,
+    pub grace_ends_at: Option<u64>,
+    // Only known when the query passes the current time
+    pub status: Option<AliasStatus>,
+}
+
+// The primary alias of an address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReverseLookupResponse {
+    pub address: HumanAddr,
+    pub alias: String,
+    pub avatar_url: Option<String>,
+    pub expires_at: Option<u64>,
+    pub grace_ends_at: Option<u64>,
+    pub status: Option<AliasStatus>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct SearchResponse {
     pub r#type: String,
     pub attributes: AliasAttributes,
+    pub grace_ends_at: Option<u64>,
+    pub status: Option<AliasStatus>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenPrice {
+    pub token: SecretContract,
+    pub fee: Uint128,
 }
 
 // === ENUMS ===
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasStatus {
+    Active,
+    ExpiredInGrace,
+}
+
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum HandleAnswer {
+    AcceptAdmin { status: ResponseStatus },
+    AddReservedAlias { status: ResponseStatus },
+    AssignReservedAlias { status: ResponseStatus },
+    CancelAdminProposal { status: ResponseStatus },
+    Create { status: ResponseStatus },
+    CreateSubAlias { status: ResponseStatus },
     Destroy { status: ResponseStatus },
+    ProposeAdmin { status: ResponseStatus },
+    RemoveReservedAlias { status: ResponseStatus },
+    Renew { status: ResponseStatus },
+    RevokeSubAlias { status: ResponseStatus },
+    SetPrimary { status: ResponseStatus },
+    Transfer { status: ResponseStatus },
+    UpdateAlias { status: ResponseStatus },
+    UpdateConfig { status: ResponseStatus },
+    UpdateSubAlias { status: ResponseStatus },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum HandleMsg {
+    AcceptAdmin {},
+    AddReservedAlias {
+        alias: String,
+    },
+    AssignReservedAlias {
+        alias: String,
+        address: HumanAddr,
+    },
+    CancelAdminProposal {},
+    Create {
+        alias: String,
+        avatar_url: Option<String>,
+    },
+    CreateSubAlias {
+        alias: String,
+        address: HumanAddr,
+        avatar_url: Option<String>,
+    },
     Destroy {
         alias: String,
     },
+    ProposeAdmin {
+        address: HumanAddr,
+    },
+    RemoveReservedAlias {
+        alias: String,
+    },
     Receive {
         sender: HumanAddr,
         from: HumanAddr,
         amount: Uint128,
         msg: Binary,
     },
+    Renew {
+        alias: String,
+    },
+    RevokeSubAlias {
+        alias: String,
+    },
+    SetPrimary {
+        alias: String,
+    },
+    Transfer {
+        alias: String,
+        recipient: HumanAddr,
+    },
+    UpdateAlias {
+        alias: String,
+        avatar_url: Option<String>,
+    },
+    UpdateConfig {
+        payment_tokens: Option<Vec<PaymentToken>>,
+        native_payments: Option<Vec<NativePayment>>,
+        registration_period: Option<u64>,
+        grace_period: Option<u64>,
+        max_batch_size: Option<u16>,
+    },
+    UpdateSubAlias {
+        alias: String,
+        address: HumanAddr,
+        avatar_url: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryAnswer {
+    Aliases {
+        primary: Option<String>,
+        aliases: Vec<AliasAttributes>,
+    },
     Config {
-        buttcoin: SecretContract,
-        butt_lode: SecretContract,
+        admin: HumanAddr,
+        pending_admin: Option<HumanAddr>,
+        payment_tokens: Vec<PaymentToken>,
+        native_payments: Vec<NativePayment>,
+        registration_period: u64,
+        grace_period: u64,
+        max_batch_size: u16,
+    },
+    ListAliases {
+        aliases: Vec<AliasAttributes>,
+        // Pass as start_after to get the next page, null once the list has been read to its end
+        next_start_after: Option<u64>,
+    },
+    Lookalike {
+        alias: Option<String>,
+    },
+    Price {
+        alias: String,
+        prices: Vec<TokenPrice>,
+        native_prices: Vec<Coin>,
+    },
+    ReservedAliases {
+        reserved_aliases: Vec<ReservedAlias>,
+        // Pass as start_after to get the next page, null once the list has been read to its end
+        next_start_after: Option<u64>,
+    },
+    SearchPrefix {
+        aliases: Vec<AliasAttributes>,
     },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
+    Aliases {
+        address: HumanAddr,
+    },
+    BatchResolve {
+        aliases: Vec<String>,
+        current_time: Option<u64>,
+    },
+    BatchReverseLookup {
+        addresses: Vec<HumanAddr>,
+        current_time: Option<u64>,
+    },
     Config {},
+    ListAliases {
+        start_after: Option<u64>,
+        limit: Option<u16>,
+    },
+    Lookalike {
+        alias: String,
+    },
+    Price {
+        alias: String,
+    },
+    ReservedAliases {
+        start_after: Option<u64>,
+        limit: Option<u16>,
+    },
+    ResolveAlias {
+        alias: String,
+        current_time: Option<u64>,
+    },
+    ReverseLookup {
+        address: HumanAddr,
+        current_time: Option<u64>,
+    },
+    /// Deprecated, use resolve_alias or reverse_lookup instead
     Search {
         search_type: String,
         search_value: String,
+        current_time: Option<u64>,
+    },
+    SearchPrefix {
+        prefix: String,
+        limit: Option<u16>,
     },
 }
 
@@ -69,6 +259,8 @@ pub enum QueryMsg {
 #[serde(rename_all = "snake_case")]
 pub enum ReceiveAnswer {
     Create { status: ResponseStatus },
+    Rename { status: ResponseStatus },
+    Renew { status: ResponseStatus },
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
@@ -78,6 +270,13 @@ pub enum ReceiveMsg {
         alias: String,
         avatar_url: Option<String>,
     },
+    Rename {
+        from_alias: String,
+        to_alias: String,
+    },
+    Renew {
+        alias: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -1,4 +1,4 @@
-use cosmwasm_std::{HumanAddr, ReadonlyStorage, StdResult, Storage};
+use cosmwasm_std::{HumanAddr, ReadonlyStorage, StdResult, Storage, Uint128};
 use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
 use schemars::JsonSchema;
 use secret_toolkit::serialization::{Bincode2, Serde};
@@ -7,19 +7,84 @@ use serde::{Deserialize, Serialize};
 
 // === CONSTANTS ===
 pub const ADDRESSES_ALIASES_PREFIX: &[u8] = b"addresses_aliases";
+pub const ALIASES_LIST_IDS_PREFIX: &[u8] = b"aliases_list_ids";
+pub const ALIASES_LIST_PREFIX: &[u8] = b"aliases_list";
 pub const ALIASES_PREFIX: &[u8] = b"aliases";
+pub const ALIASES_TRIE_PREFIX: &[u8] = b"aliases_trie";
+pub const RESERVED_ALIASES_LIST_IDS_PREFIX: &[u8] = b"reserved_aliases_list_ids";
+pub const RESERVED_ALIASES_LIST_PREFIX: &[u8] = b"reserved_aliases_list";
+pub const RESERVED_ALIASES_PREFIX: &[u8] = b"reserved_aliases";
+pub const SKELETONS_PREFIX: &[u8] = b"skeletons";
+pub const SUB_ALIASES_PREFIX: &[u8] = b"sub_aliases";
 
 // === STRUCTS ===
+// The aliases an address holds, primary is what a search by address returns
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AddressAliases {
+    pub aliases: Vec<String>,
+    pub primary: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Alias {
     pub human_address: HumanAddr,
     pub avatar_url: Option<String>,
+    pub expires_at: Option<u64>,
+    // Set for sub-aliases, which are managed by the owner of this alias
+    pub parent: Option<String>,
+}
+
+// A prefix of one or more aliases, children are the characters that can follow it in order
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct AliasesTrieNode {
+    pub children: Vec<char>,
+    pub is_alias: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Config {
-    pub buttcoin: SecretContract,
-    pub butt_lode: SecretContract,
+    pub admin: HumanAddr,
+    // Seconds after expiry during which only the previous owner can renew
+    pub grace_period: u64,
+    // Most aliases or addresses a batch query accepts, and most aliases a page or search returns
+    pub max_batch_size: u16,
+    pub native_payments: Vec<NativePayment>,
+    pub payment_tokens: Vec<PaymentToken>,
+    pub pending_admin: Option<HumanAddr>,
+    // Seconds an alias lasts for when created or renewed, 0 means they never expire
+    pub registration_period: u64,
+}
+
+// Overrides the fee for aliases of exactly this many bytes once formatted
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, JsonSchema)]
+pub struct LengthPrice {
+    pub length: u8,
+    pub fee: Uint128,
+}
+
+// A native denom accepted as payment, with the fee charged in it and who receives that fee
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, JsonSchema)]
+pub struct NativePayment {
+    pub denom: String,
+    pub fee_recipient: HumanAddr,
+    pub fee: Uint128,
+    pub length_prices: Vec<LengthPrice>,
+}
+
+// A SNIP-20 token accepted as payment, with the fee charged in it and who receives that fee
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, JsonSchema)]
+pub struct PaymentToken {
+    pub token: SecretContract,
+    pub fee_recipient: HumanAddr,
+    pub fee: Uint128,
+    pub length_prices: Vec<LengthPrice>,
+}
+
+// An alias held back by the admin, only the address it is reserved for can create it
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, JsonSchema)]
+pub struct ReservedAlias {
+    pub alias: String,
+    pub address: Option<HumanAddr>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, JsonSchema)]
@@ -39,7 +104,7 @@ impl<'a, S: Storage> AliasesReadonlyStorage<'a, S> {
         }
     }
 
-    pub fn get_alias(&self, key: &[u8]) -> Option<Alias> {
+    pub fn get_alias(&self, key: &[u8]) -> StdResult<Option<Alias>> {
         self.as_readonly().get(key)
     }
 
@@ -60,16 +125,16 @@ impl<'a, S: Storage> AliasesStorage<'a, S> {
         }
     }
 
-    pub fn get_alias(&mut self, key: &[u8]) -> Option<Alias> {
+    pub fn get_alias(&mut self, key: &[u8]) -> StdResult<Option<Alias>> {
         self.as_readonly().get(key)
     }
 
-    pub fn remove_alias(&mut self, key: &[u8]) {
-        remove(&mut self.storage, &key);
+    pub fn remove_alias(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
     }
 
-    pub fn set_alias(&mut self, key: &[u8], value: Alias) {
-        save(&mut self.storage, &key, &value).ok();
+    pub fn set_alias(&mut self, key: &[u8], value: Alias) -> StdResult<()> {
+        save(&mut self.storage, &key, &value)
     }
 
     // private
@@ -81,9 +146,158 @@ impl<'a, S: Storage> AliasesStorage<'a, S> {
 
 struct ReadonlyAliasesStorageImpl<'a, S: ReadonlyStorage>(&'a S);
 impl<'a, S: ReadonlyStorage> ReadonlyAliasesStorageImpl<'a, S> {
-    pub fn get(&self, key: &[u8]) -> Option<Alias> {
-        let alias: Option<Alias> = may_load(self.0, &key).ok().unwrap();
-        alias
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<Alias>> {
+        may_load(self.0, &key)
+    }
+}
+
+// === AliasesList Storage ===
+// Maps a sequence id to the alias registered under it, ids are handed out in the order
+// aliases are registered and never reused
+
+pub struct AliasesListReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasesListReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(ALIASES_LIST_PREFIX, storage),
+        }
+    }
+
+    pub fn get_alias(&self, key: &[u8]) -> StdResult<Option<String>> {
+        self.as_readonly().get(key)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlyAliasesListStorageImpl<ReadonlyPrefixedStorage<S>> {
+        ReadonlyAliasesListStorageImpl(&self.storage)
+    }
+}
+
+pub struct AliasesListStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasesListStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ALIASES_LIST_PREFIX, storage),
+        }
+    }
+
+    pub fn remove_alias(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
+    }
+
+    pub fn set_alias(&mut self, key: &[u8], value: &str) -> StdResult<()> {
+        save(&mut self.storage, key, &value)
+    }
+}
+
+struct ReadonlyAliasesListStorageImpl<'a, S: ReadonlyStorage>(&'a S);
+impl<'a, S: ReadonlyStorage> ReadonlyAliasesListStorageImpl<'a, S> {
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<String>> {
+        may_load(self.0, &key)
+    }
+}
+
+// === AliasesListIds Storage ===
+// Maps an alias to its sequence id in the list of aliases
+
+pub struct AliasesListIdsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasesListIdsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ALIASES_LIST_IDS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_id(&mut self, key: &[u8]) -> StdResult<Option<u64>> {
+        self.as_readonly().get(key)
+    }
+
+    pub fn remove_id(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
+    }
+
+    pub fn set_id(&mut self, key: &[u8], value: u64) -> StdResult<()> {
+        save(&mut self.storage, key, &value)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlyAliasesListIdsStorageImpl<PrefixedStorage<S>> {
+        ReadonlyAliasesListIdsStorageImpl(&self.storage)
+    }
+}
+
+struct ReadonlyAliasesListIdsStorageImpl<'a, S: ReadonlyStorage>(&'a S);
+impl<'a, S: ReadonlyStorage> ReadonlyAliasesListIdsStorageImpl<'a, S> {
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<u64>> {
+        may_load(self.0, &key)
+    }
+}
+
+// === AliasesTrie Storage ===
+// Maps every prefix of every alias to its node in the trie
+
+pub struct AliasesTrieReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasesTrieReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(ALIASES_TRIE_PREFIX, storage),
+        }
+    }
+
+    pub fn get_node(&self, key: &[u8]) -> StdResult<Option<AliasesTrieNode>> {
+        self.as_readonly().get(key)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlyAliasesTrieStorageImpl<ReadonlyPrefixedStorage<S>> {
+        ReadonlyAliasesTrieStorageImpl(&self.storage)
+    }
+}
+
+pub struct AliasesTrieStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasesTrieStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ALIASES_TRIE_PREFIX, storage),
+        }
+    }
+
+    pub fn get_node(&mut self, key: &[u8]) -> StdResult<Option<AliasesTrieNode>> {
+        self.as_readonly().get(key)
+    }
+
+    pub fn remove_node(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
+    }
+
+    pub fn set_node(&mut self, key: &[u8], value: &AliasesTrieNode) -> StdResult<()> {
+        save(&mut self.storage, key, value)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlyAliasesTrieStorageImpl<PrefixedStorage<S>> {
+        ReadonlyAliasesTrieStorageImpl(&self.storage)
+    }
+}
+
+struct ReadonlyAliasesTrieStorageImpl<'a, S: ReadonlyStorage>(&'a S);
+impl<'a, S: ReadonlyStorage> ReadonlyAliasesTrieStorageImpl<'a, S> {
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<AliasesTrieNode>> {
+        may_load(self.0, &key)
     }
 }
 
@@ -99,7 +313,7 @@ impl<'a, S: Storage> AddressesAliasesReadonlyStorage<'a, S> {
         }
     }
 
-    pub fn get_alias(&self, key: &String) -> Option<Vec<u8>> {
+    pub fn get_aliases(&self, key: &String) -> StdResult<Option<AddressAliases>> {
         self.as_readonly().get(key)
     }
 
@@ -120,16 +334,16 @@ impl<'a, S: Storage> AddressesAliasesStorage<'a, S> {
         }
     }
 
-    pub fn get_alias(&mut self, key: &String) -> Option<Vec<u8>> {
+    pub fn get_aliases(&mut self, key: &String) -> StdResult<Option<AddressAliases>> {
         self.as_readonly().get(key)
     }
 
-    pub fn remove_alias(&mut self, key: &[u8]) {
-        remove(&mut self.storage, &key);
+    pub fn remove_aliases(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
     }
 
-    pub fn set_alias(&mut self, key: &[u8], value: &String) {
-        save(&mut self.storage, key, value).ok();
+    pub fn set_aliases(&mut self, key: &[u8], value: &AddressAliases) -> StdResult<()> {
+        save(&mut self.storage, key, value)
     }
 
     // private
@@ -141,9 +355,256 @@ impl<'a, S: Storage> AddressesAliasesStorage<'a, S> {
 
 struct ReadonlyAddressesAliasesStorageImpl<'a, S: ReadonlyStorage>(&'a S);
 impl<'a, S: ReadonlyStorage> ReadonlyAddressesAliasesStorageImpl<'a, S> {
-    pub fn get(&self, key: &String) -> Option<Vec<u8>> {
-        let alias: Option<Vec<u8>> = may_load(self.0, &key.as_bytes()).ok().unwrap();
-        alias
+    pub fn get(&self, key: &String) -> StdResult<Option<AddressAliases>> {
+        may_load(self.0, &key.as_bytes())
+    }
+}
+
+// === ReservedAliases Storage ===
+
+pub struct ReservedAliasesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReservedAliasesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(RESERVED_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_reserved_alias(&self, key: &[u8]) -> StdResult<Option<ReservedAlias>> {
+        self.as_readonly().get(key)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlyReservedAliasesStorageImpl<ReadonlyPrefixedStorage<S>> {
+        ReadonlyReservedAliasesStorageImpl(&self.storage)
+    }
+}
+
+pub struct ReservedAliasesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReservedAliasesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(RESERVED_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_reserved_alias(&mut self, key: &[u8]) -> StdResult<Option<ReservedAlias>> {
+        self.as_readonly().get(key)
+    }
+
+    pub fn remove_reserved_alias(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
+    }
+
+    pub fn set_reserved_alias(&mut self, key: &[u8], value: &ReservedAlias) -> StdResult<()> {
+        save(&mut self.storage, key, value)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlyReservedAliasesStorageImpl<PrefixedStorage<S>> {
+        ReadonlyReservedAliasesStorageImpl(&self.storage)
+    }
+}
+
+struct ReadonlyReservedAliasesStorageImpl<'a, S: ReadonlyStorage>(&'a S);
+impl<'a, S: ReadonlyStorage> ReadonlyReservedAliasesStorageImpl<'a, S> {
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<ReservedAlias>> {
+        may_load(self.0, &key)
+    }
+}
+
+// === ReservedAliasesList Storage ===
+// Maps a sequence id to the alias reserved under it, ids are handed out in the order
+// aliases are reserved and never reused
+
+pub struct ReservedAliasesListReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReservedAliasesListReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(RESERVED_ALIASES_LIST_PREFIX, storage),
+        }
+    }
+
+    pub fn get_alias(&self, key: &[u8]) -> StdResult<Option<String>> {
+        self.as_readonly().get(key)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlyReservedAliasesListStorageImpl<ReadonlyPrefixedStorage<S>> {
+        ReadonlyReservedAliasesListStorageImpl(&self.storage)
+    }
+}
+
+pub struct ReservedAliasesListStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReservedAliasesListStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(RESERVED_ALIASES_LIST_PREFIX, storage),
+        }
+    }
+
+    pub fn remove_alias(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
+    }
+
+    pub fn set_alias(&mut self, key: &[u8], value: &str) -> StdResult<()> {
+        save(&mut self.storage, key, &value)
+    }
+}
+
+struct ReadonlyReservedAliasesListStorageImpl<'a, S: ReadonlyStorage>(&'a S);
+impl<'a, S: ReadonlyStorage> ReadonlyReservedAliasesListStorageImpl<'a, S> {
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<String>> {
+        may_load(self.0, &key)
+    }
+}
+
+// === ReservedAliasesListIds Storage ===
+// Maps a reserved alias to its sequence id in the list of reserved aliases
+
+pub struct ReservedAliasesListIdsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReservedAliasesListIdsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(RESERVED_ALIASES_LIST_IDS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_id(&mut self, key: &[u8]) -> StdResult<Option<u64>> {
+        self.as_readonly().get(key)
+    }
+
+    pub fn remove_id(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
+    }
+
+    pub fn set_id(&mut self, key: &[u8], value: u64) -> StdResult<()> {
+        save(&mut self.storage, key, &value)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlyReservedAliasesListIdsStorageImpl<PrefixedStorage<S>> {
+        ReadonlyReservedAliasesListIdsStorageImpl(&self.storage)
+    }
+}
+
+struct ReadonlyReservedAliasesListIdsStorageImpl<'a, S: ReadonlyStorage>(&'a S);
+impl<'a, S: ReadonlyStorage> ReadonlyReservedAliasesListIdsStorageImpl<'a, S> {
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<u64>> {
+        may_load(self.0, &key)
+    }
+}
+
+// === Skeletons Storage ===
+// Maps the confusable skeleton of an alias to the alias itself
+
+pub struct SkeletonsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> SkeletonsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(SKELETONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_alias(&self, key: &[u8]) -> StdResult<Option<String>> {
+        self.as_readonly().get(key)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlySkeletonsStorageImpl<ReadonlyPrefixedStorage<S>> {
+        ReadonlySkeletonsStorageImpl(&self.storage)
+    }
+}
+
+pub struct SkeletonsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> SkeletonsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(SKELETONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_alias(&mut self, key: &[u8]) -> StdResult<Option<String>> {
+        self.as_readonly().get(key)
+    }
+
+    pub fn remove_alias(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
+    }
+
+    pub fn set_alias(&mut self, key: &[u8], value: &String) -> StdResult<()> {
+        save(&mut self.storage, key, value)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlySkeletonsStorageImpl<PrefixedStorage<S>> {
+        ReadonlySkeletonsStorageImpl(&self.storage)
+    }
+}
+
+struct ReadonlySkeletonsStorageImpl<'a, S: ReadonlyStorage>(&'a S);
+impl<'a, S: ReadonlyStorage> ReadonlySkeletonsStorageImpl<'a, S> {
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<String>> {
+        may_load(self.0, &key)
+    }
+}
+
+// === SubAliases Storage ===
+// Maps an alias to its sub-aliases, so that they can be revoked along with it
+
+pub struct SubAliasesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> SubAliasesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(SUB_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_sub_aliases(&mut self, key: &[u8]) -> StdResult<Option<Vec<String>>> {
+        self.as_readonly().get(key)
+    }
+
+    pub fn remove_sub_aliases(&mut self, key: &[u8]) -> StdResult<()> {
+        remove(&mut self.storage, &key)
+    }
+
+    pub fn set_sub_aliases(&mut self, key: &[u8], value: &[String]) -> StdResult<()> {
+        save(&mut self.storage, key, &value)
+    }
+
+    // private
+
+    fn as_readonly(&self) -> ReadonlySubAliasesStorageImpl<PrefixedStorage<S>> {
+        ReadonlySubAliasesStorageImpl(&self.storage)
+    }
+}
+
+struct ReadonlySubAliasesStorageImpl<'a, S: ReadonlyStorage>(&'a S);
+impl<'a, S: ReadonlyStorage> ReadonlySubAliasesStorageImpl<'a, S> {
+    pub fn get(&self, key: &[u8]) -> StdResult<Option<Vec<String>>> {
+        may_load(self.0, &key)
     }
 }
 
@@ -158,8 +619,9 @@ fn may_load<T: DeserializeOwned, S: ReadonlyStorage>(
     }
 }
 
-fn remove<S: Storage>(storage: &mut S, key: &[u8]) {
+fn remove<S: Storage>(storage: &mut S, key: &[u8]) -> StdResult<()> {
     storage.remove(key);
+    Ok(())
 }
 
 fn save<T: Serialize, S: Storage>(storage: &mut S, key: &[u8], value: &T) -> StdResult<()> {
@@ -0,0 +1,59 @@
+use crate::error::ContractError;
+use cosmwasm_std::StdResult;
+
+pub const ALIAS_MAX_LENGTH: usize = 255;
+pub const ALIAS_MIN_LENGTH: usize = 1;
+// Besides lowercase a-z and 0-9, aliases may contain these characters
+pub const ALIAS_PUNCTUATION: &[char] = &[' ', '.', '-', '_', '\'', '!'];
+pub const AVATAR_URL_MAX_LENGTH: usize = 512;
+pub const AVATAR_URL_SCHEMES: &[&str] = &["https", "ipfs", "ar"];
+
+pub fn validate_alias(alias: &str) -> StdResult<()> {
+    if alias.len() < ALIAS_MIN_LENGTH {
+        return Err(ContractError::AliasTooShort.into());
+    }
+    if alias.len() > ALIAS_MAX_LENGTH {
+        return Err(ContractError::AliasTooLong.into());
+    }
+    if let Some(c) = alias
+        .chars()
+        .find(|c| !c.is_ascii_lowercase() && !c.is_ascii_digit() && !ALIAS_PUNCTUATION.contains(c))
+    {
+        return Err(ContractError::AliasInvalidCharacter { character: c }.into());
+    }
+    if alias.starts_with(' ') || alias.ends_with(' ') {
+        return Err(ContractError::AliasSurroundingSpace.into());
+    }
+    if alias.contains("  ") {
+        return Err(ContractError::AliasRepeatedSpaces.into());
+    }
+
+    Ok(())
+}
+
+pub fn validate_avatar_url(avatar_url: Option<&str>) -> StdResult<()> {
+    let avatar_url: &str = match avatar_url {
+        Some(avatar_url) => avatar_url,
+        None => return Ok(()),
+    };
+    if avatar_url.len() > AVATAR_URL_MAX_LENGTH {
+        return Err(ContractError::AvatarUrlTooLong.into());
+    }
+    if avatar_url
+        .chars()
+        .any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Err(ContractError::AvatarUrlInvalidCharacter.into());
+    }
+    let location: &str = match avatar_url.find("://") {
+        Some(index) if AVATAR_URL_SCHEMES.contains(&&avatar_url[..index]) => {
+            &avatar_url[index + 3..]
+        }
+        _ => return Err(ContractError::AvatarUrlInvalidScheme.into()),
+    };
+    if location.is_empty() {
+        return Err(ContractError::AvatarUrlMissingLocation.into());
+    }
+
+    Ok(())
+}